@@ -0,0 +1,47 @@
+//! Builds the WebAuthn account WASM that the factory tests deploy
+//!
+//! The account is built into its own target directory under `OUT_DIR`, so
+//! this does not wait on the lock of the build that runs this script.
+
+use std::{env, path::PathBuf, process::Command};
+
+// Recent Rust versions emit reference types for wasm32-unknown-unknown, which
+// the Soroban host rejects
+const WASM_TARGET: &str = "wasm32v1-none";
+
+fn main() {
+    println!("cargo:rerun-if-changed=../webauthn-account/src");
+    println!("cargo:rerun-if-changed=../webauthn-account/Cargo.toml");
+    println!("cargo:rerun-if-changed=../../crates/passkey-types/src");
+
+    // The factory's own WASM build does not need it, only the host tests
+    if env::var("TARGET").unwrap().starts_with("wasm32") {
+        return;
+    }
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let target_dir = out_dir.join("webauthn-account");
+    let status = Command::new(env::var("CARGO").unwrap())
+        .args(["build", "--package", "webauthn-account", "--release"])
+        .args(["--target", WASM_TARGET])
+        .arg("--target-dir")
+        .arg(&target_dir)
+        // Flags meant for this build, not for the nested one
+        .env_remove("CARGO_ENCODED_RUSTFLAGS")
+        .env_remove("RUSTC_WORKSPACE_WRAPPER")
+        .status()
+        .expect("failed to run cargo");
+    assert!(
+        status.success(),
+        "building the WebAuthn account WASM failed (is the {} target installed?)",
+        WASM_TARGET
+    );
+
+    std::fs::copy(
+        target_dir
+            .join(WASM_TARGET)
+            .join("release/webauthn_account.wasm"),
+        out_dir.join("webauthn_account.wasm"),
+    )
+    .unwrap();
+}
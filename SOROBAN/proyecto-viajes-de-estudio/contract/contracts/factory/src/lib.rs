@@ -0,0 +1,574 @@
+#![no_std]
+use passkey_types::public_key;
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, vec, Address, Bytes, BytesN,
+    Env, String, Symbol, Vec,
+};
+
+#[contract]
+pub struct Factory;
+
+#[contracterror]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Error {
+    NotInited = 1,
+    AlreadyInited = 2,
+    AlreadyDeployed = 3,
+    CredentialExists = 4,
+    KeyExists = 5,
+    InvalidPublicKey = 6,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    /// Marca las cuentas desplegadas por el factory
+    Deployed(Address),
+    /// Credential id de la passkey -> cuenta
+    Credential(Bytes),
+    /// Clave pública (SEC1 sin comprimir) -> cuenta
+    Key(BytesN<65>),
+}
+
+const STORAGE_KEY_WASM_HASH: Symbol = symbol_short!("hash");
+const STORAGE_KEY_RP_ID_HASH: Symbol = symbol_short!("rpid");
+const STORAGE_KEY_ORIGINS: Symbol = symbol_short!("origins");
+
+#[contractimpl]
+impl Factory {
+    /// Extiende el TTL del contrato
+    pub fn extend_ttl(env: Env) {
+        let max_ttl = env.storage().max_ttl();
+        let contract_address = env.current_contract_address();
+
+        env.storage().instance().extend_ttl(max_ttl, max_ttl);
+        env.deployer()
+            .extend_ttl(contract_address.clone(), max_ttl, max_ttl);
+        env.deployer()
+            .extend_ttl_for_code(contract_address.clone(), max_ttl, max_ttl);
+        env.deployer()
+            .extend_ttl_for_contract_instance(contract_address.clone(), max_ttl, max_ttl);
+    }
+
+    /// Inicializa el factory con el hash del contrato WebAuthn, el hash del
+    /// rpId y los orígenes con los que se inicializan las cuentas desplegadas
+    pub fn init(
+        env: Env,
+        wasm_hash: BytesN<32>,
+        rp_id_hash: BytesN<32>,
+        origins: Vec<String>,
+    ) -> Result<(), Error> {
+        if env.storage().instance().has(&STORAGE_KEY_WASM_HASH) {
+            return Err(Error::AlreadyInited);
+        }
+
+        env.storage()
+            .instance()
+            .set(&STORAGE_KEY_WASM_HASH, &wasm_hash);
+        env.storage()
+            .instance()
+            .set(&STORAGE_KEY_RP_ID_HASH, &rp_id_hash);
+        env.storage().instance().set(&STORAGE_KEY_ORIGINS, &origins);
+
+        Self::extend_ttl(env);
+
+        Ok(())
+    }
+
+    /// Despliega una nueva cuenta WebAuthn. La clave pública se acepta en
+    /// cualquiera de los formatos de `WebAuthnAccount::init`, incluido el
+    /// COSE_Key tal como llega en la respuesta de registro. El credential id
+    /// y la clave quedan registrados para encontrar la cuenta en el login,
+    /// pero solo después de que la propia cuenta autorice el despliegue: la
+    /// passkey de `pk` tiene que firmar esta llamada (con sus argumentos), así
+    /// que nadie puede registrar la clave de otro.
+    ///
+    /// El salt se deriva del credential id y de la clave, así que cada
+    /// credential id solo puede ocupar la dirección de su propia clave: otra
+    /// clave con el mismo credential id falla con `CredentialExists`.
+    pub fn deploy(env: Env, credential_id: Bytes, pk: Bytes) -> Result<Address, Error> {
+        let pk = public_key::to_uncompressed(&pk).ok_or(Error::InvalidPublicKey)?;
+        let salt = Self::salt(&env, &credential_id, &pk);
+        if Self::is_deployed(&env, &Self::address_for(&env, &salt)) {
+            return Err(Error::AlreadyDeployed);
+        }
+
+        Self::deploy_account(env, salt, credential_id, pk)
+    }
+
+    /// Igual que `deploy`, pero si la cuenta de ese credential id y esa clave
+    /// ya existe devuelve su dirección sin volver a desplegarla ni
+    /// inicializarla
+    pub fn deploy_if_absent(env: Env, credential_id: Bytes, pk: Bytes) -> Result<Address, Error> {
+        let pk = public_key::to_uncompressed(&pk).ok_or(Error::InvalidPublicKey)?;
+        let salt = Self::salt(&env, &credential_id, &pk);
+        let address = Self::address_for(&env, &salt);
+        if Self::is_deployed(&env, &address) {
+            return Ok(address);
+        }
+
+        Self::deploy_account(env, salt, credential_id, pk)
+    }
+
+    /// Devuelve la cuenta desplegada para un credential id
+    pub fn lookup_by_credential(env: Env, credential_id: Bytes) -> Option<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Credential(credential_id))
+    }
+
+    /// Devuelve la cuenta desplegada para una clave pública SEC1 sin comprimir
+    pub fn lookup_by_key(env: Env, pk: BytesN<65>) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::Key(pk))
+    }
+
+    /// Calcula la dirección que tendrá (o tiene) la cuenta de ese credential
+    /// id y esa clave, sin desplegar nada. Permite conocerla antes de la
+    /// primera transacción, por ejemplo para fondearla.
+    ///
+    /// Recibe el credential id y la clave en lugar de un salt libre
+    /// (`get_address(salt)`): con un salt elegido por el cliente, quien
+    /// desplegara primero pondría su propia clave en la dirección que el
+    /// usuario ya calculó y quizá fondeó. `deploy` y `deploy_if_absent`
+    /// reciben los mismos argumentos y derivan el mismo salt.
+    pub fn get_address(env: Env, credential_id: Bytes, pk: Bytes) -> Result<Address, Error> {
+        let pk = public_key::to_uncompressed(&pk).ok_or(Error::InvalidPublicKey)?;
+        Ok(Self::address_for(
+            &env,
+            &Self::salt(&env, &credential_id, &pk),
+        ))
+    }
+
+    /// Salt de la cuenta: sha256(credential id || clave SEC1 sin comprimir)
+    fn salt(env: &Env, credential_id: &Bytes, pk: &[u8; 65]) -> BytesN<32> {
+        let mut data = credential_id.clone();
+        data.extend_from_array(pk);
+        env.crypto().sha256(&data).to_bytes()
+    }
+
+    fn address_for(env: &Env, salt: &BytesN<32>) -> Address {
+        env.deployer()
+            .with_current_contract(salt.clone())
+            .deployed_address()
+    }
+
+    fn is_deployed(env: &Env, address: &Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::Deployed(address.clone()))
+    }
+
+    fn deploy_account(
+        env: Env,
+        salt: BytesN<32>,
+        credential_id: Bytes,
+        pk: [u8; 65],
+    ) -> Result<Address, Error> {
+        let credential_key = DataKey::Credential(credential_id);
+        if env.storage().persistent().has(&credential_key) {
+            return Err(Error::CredentialExists);
+        }
+        // Se registra la clave normalizada, la misma que guarda la cuenta
+        let pk = BytesN::from_array(&env, &pk);
+        let public_key_key = DataKey::Key(pk.clone());
+        if env.storage().persistent().has(&public_key_key) {
+            return Err(Error::KeyExists);
+        }
+
+        let wasm_hash = env
+            .storage()
+            .instance()
+            .get::<Symbol, BytesN<32>>(&STORAGE_KEY_WASM_HASH)
+            .ok_or(Error::NotInited)?;
+        let rp_id_hash = env
+            .storage()
+            .instance()
+            .get::<Symbol, BytesN<32>>(&STORAGE_KEY_RP_ID_HASH)
+            .ok_or(Error::NotInited)?;
+        let origins = env
+            .storage()
+            .instance()
+            .get::<Symbol, Vec<String>>(&STORAGE_KEY_ORIGINS)
+            .ok_or(Error::NotInited)?;
+
+        let address = env.deployer().with_current_contract(salt).deploy(wasm_hash);
+        let pk: Bytes = pk.into();
+        let () = env.invoke_contract(
+            &address,
+            &symbol_short!("init"),
+            vec![&env, pk.to_val(), rp_id_hash.to_val(), origins.to_val()],
+        );
+        // Prueba de posesión: la cuenta recién inicializada verifica con su
+        // `__check_auth` la firma WebAuthn de `pk` sobre esta invocación
+        // (credential id incluido) antes de escribir el registro
+        address.require_auth();
+
+        let max_ttl = env.storage().max_ttl();
+        let deployed_key = DataKey::Deployed(address.clone());
+        env.storage().persistent().set(&deployed_key, &());
+        env.storage().persistent().set(&credential_key, &address);
+        env.storage().persistent().set(&public_key_key, &address);
+        for key in [deployed_key, credential_key, public_key_key] {
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, max_ttl, max_ttl);
+        }
+
+        Self::extend_ttl(env);
+
+        Ok(address)
+    }
+}
+
+#[cfg(test)]
+mod factory_test {
+    extern crate std;
+
+    use super::*;
+    use p256::ecdsa::SigningKey;
+    use soroban_sdk::{
+        contractclient,
+        testutils::{AuthorizedFunction, AuthorizedInvocation, MockAuth, MockAuthInvoke},
+        IntoVal,
+    };
+
+    // WASM real de la cuenta WebAuthn, compilado por build.rs
+    const WEBAUTHN_ACCOUNT_WASM: &[u8] =
+        include_bytes!(concat!(env!("OUT_DIR"), "/webauthn_account.wasm"));
+
+    /// Las funciones de la cuenta WebAuthn que usan los tests (solo genera
+    /// el cliente)
+    #[allow(dead_code)]
+    #[contractclient(name = "WebAuthnAccountClient")]
+    trait WebAuthnAccount {
+        fn get_public_key(env: Env) -> BytesN<65>;
+        fn get_origins(env: Env) -> Vec<String>;
+    }
+
+    const ORIGIN: &str = "https://ebas.example";
+
+    /// 32 bytes derivados de `seed`, en lugar de valores aleatorios para que
+    /// los snapshots de los tests sean iguales en cada ejecución
+    fn fixed_bytes(env: &Env, seed: u8) -> BytesN<32> {
+        env.crypto()
+            .sha256(&Bytes::from_array(env, &[seed]))
+            .to_bytes()
+    }
+
+    /// Factory inicializado. Las cuentas desplegadas autorizan su despliegue
+    /// (las firmas de las passkeys se simulan con `mock_all_auths`).
+    fn setup(env: &Env) -> FactoryClient<'_> {
+        env.mock_all_auths();
+        let wasm_hash = env.deployer().upload_contract_wasm(WEBAUTHN_ACCOUNT_WASM);
+        let factory_id = env.register_contract(None, Factory);
+        let client = FactoryClient::new(env, &factory_id);
+        client.init(
+            &wasm_hash,
+            &fixed_bytes(env, 1),
+            &vec![env, String::from_str(env, ORIGIN)],
+        );
+        client
+    }
+
+    fn public_key(seed: u8) -> [u8; 65] {
+        let key = SigningKey::from_slice(&[seed; 32]).unwrap();
+        key.verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()
+            .try_into()
+            .unwrap()
+    }
+
+    fn credential_id(env: &Env, seed: u8) -> Bytes {
+        Bytes::from_array(env, &[seed; 16])
+    }
+
+    #[test]
+    fn test_deploy_webauthn_account() {
+        let env = Env::default();
+        let factory = setup(&env);
+        let pk = public_key(7);
+
+        let address = factory.deploy(&credential_id(&env, 1), &Bytes::from_array(&env, &pk));
+
+        let account = WebAuthnAccountClient::new(&env, &address);
+        assert_eq!(account.get_public_key(), BytesN::from_array(&env, &pk));
+        assert_eq!(
+            account.get_origins(),
+            vec![&env, String::from_str(&env, ORIGIN)]
+        );
+    }
+
+    #[test]
+    fn test_deploy_compressed_key() {
+        let env = Env::default();
+        let factory = setup(&env);
+        let pk = public_key(7);
+        let key = SigningKey::from_slice(&[7; 32]).unwrap();
+        let compressed = key.verifying_key().to_encoded_point(true);
+
+        let address = factory.deploy(
+            &credential_id(&env, 1),
+            &Bytes::from_slice(&env, compressed.as_bytes()),
+        );
+        let account = WebAuthnAccountClient::new(&env, &address);
+        assert_eq!(account.get_public_key(), BytesN::from_array(&env, &pk));
+    }
+
+    #[test]
+    fn test_deploy_not_inited() {
+        let env = Env::default();
+        let factory_id = env.register_contract(None, Factory);
+        let client = FactoryClient::new(&env, &factory_id);
+
+        assert_eq!(
+            client.try_deploy(
+                &credential_id(&env, 1),
+                &Bytes::from_array(&env, &public_key(7))
+            ),
+            Err(Ok(Error::NotInited))
+        );
+    }
+
+    #[test]
+    fn test_deploy_invalid_key() {
+        let env = Env::default();
+        let factory = setup(&env);
+        let mut pk = public_key(7);
+        pk[64] ^= 1;
+
+        assert_eq!(
+            factory.try_deploy(&credential_id(&env, 1), &Bytes::from_array(&env, &pk)),
+            Err(Ok(Error::InvalidPublicKey))
+        );
+        assert_eq!(
+            factory.try_get_address(&credential_id(&env, 1), &Bytes::from_array(&env, &pk)),
+            Err(Ok(Error::InvalidPublicKey))
+        );
+    }
+
+    #[test]
+    fn test_init_twice() {
+        let env = Env::default();
+        let factory = setup(&env);
+
+        assert_eq!(
+            factory.try_init(&fixed_bytes(&env, 1), &fixed_bytes(&env, 2), &vec![&env]),
+            Err(Ok(Error::AlreadyInited))
+        );
+    }
+
+    #[test]
+    fn test_get_address_matches_deploy() {
+        let env = Env::default();
+        let factory = setup(&env);
+        let id = credential_id(&env, 1);
+        let pk = Bytes::from_array(&env, &public_key(7));
+
+        let predicted = factory.get_address(&id, &pk);
+        // Consultarla no despliega nada ni cambia el resultado
+        assert_eq!(factory.get_address(&id, &pk), predicted);
+
+        let address = factory.deploy(&id, &pk);
+        assert_eq!(address, predicted);
+        assert_eq!(factory.get_address(&id, &pk), predicted);
+
+        // La dirección depende de la clave, no de cómo se codifica
+        let key = SigningKey::from_slice(&[7; 32]).unwrap();
+        let compressed = key.verifying_key().to_encoded_point(true);
+        assert_eq!(
+            factory.get_address(&id, &Bytes::from_slice(&env, compressed.as_bytes())),
+            predicted
+        );
+        assert_ne!(factory.get_address(&credential_id(&env, 2), &pk), predicted);
+        assert_ne!(
+            factory.get_address(&id, &Bytes::from_array(&env, &public_key(8))),
+            predicted
+        );
+    }
+
+    #[test]
+    fn test_deploy_twice() {
+        let env = Env::default();
+        let factory = setup(&env);
+        let pk = Bytes::from_array(&env, &public_key(7));
+
+        let id = credential_id(&env, 1);
+
+        factory.deploy(&id, &pk);
+        assert_eq!(
+            factory.try_deploy(&id, &pk),
+            Err(Ok(Error::AlreadyDeployed))
+        );
+    }
+
+    #[test]
+    fn test_deploy_if_absent() {
+        let env = Env::default();
+        let factory = setup(&env);
+        let id = credential_id(&env, 1);
+        let pk = public_key(7);
+
+        let address = factory.deploy_if_absent(&id, &Bytes::from_array(&env, &pk));
+        assert_eq!(
+            address,
+            factory.get_address(&id, &Bytes::from_array(&env, &pk))
+        );
+
+        // La segunda llamada devuelve la misma cuenta sin reinicializarla
+        let again = factory.deploy_if_absent(&id, &Bytes::from_array(&env, &pk));
+        assert_eq!(again, address);
+
+        // Con otra clave u otro credential id no se devuelve la cuenta
+        assert_eq!(
+            factory.try_deploy_if_absent(&id, &Bytes::from_array(&env, &public_key(8))),
+            Err(Ok(Error::CredentialExists))
+        );
+        assert_eq!(
+            factory.try_deploy_if_absent(&credential_id(&env, 2), &Bytes::from_array(&env, &pk)),
+            Err(Ok(Error::KeyExists))
+        );
+        let account = WebAuthnAccountClient::new(&env, &address);
+        assert_eq!(account.get_public_key(), BytesN::from_array(&env, &pk));
+    }
+
+    #[test]
+    fn test_deploy_if_absent_after_deploy() {
+        let env = Env::default();
+        let factory = setup(&env);
+        let pk = Bytes::from_array(&env, &public_key(7));
+
+        let id = credential_id(&env, 1);
+
+        let address = factory.deploy(&id, &pk);
+        assert_eq!(factory.deploy_if_absent(&id, &pk), address);
+    }
+
+    #[test]
+    fn test_lookup_registry() {
+        let env = Env::default();
+        let factory = setup(&env);
+        let pk = public_key(7);
+        let key = SigningKey::from_slice(&[7; 32]).unwrap();
+        let compressed = key.verifying_key().to_encoded_point(true);
+
+        assert_eq!(factory.lookup_by_credential(&credential_id(&env, 1)), None);
+        assert_eq!(factory.lookup_by_key(&BytesN::from_array(&env, &pk)), None);
+
+        // Se registra la clave normalizada aunque llegue comprimida
+        let address = factory.deploy(
+            &credential_id(&env, 1),
+            &Bytes::from_slice(&env, compressed.as_bytes()),
+        );
+        assert_eq!(
+            factory.lookup_by_credential(&credential_id(&env, 1)),
+            Some(address.clone())
+        );
+        assert_eq!(
+            factory.lookup_by_key(&BytesN::from_array(&env, &pk)),
+            Some(address)
+        );
+        assert_eq!(factory.lookup_by_credential(&credential_id(&env, 2)), None);
+    }
+
+    #[test]
+    fn test_deploy_requires_account_auth() {
+        let env = Env::default();
+        let factory = setup(&env);
+        let id = credential_id(&env, 1);
+        let pk = Bytes::from_array(&env, &public_key(7));
+        // Sin la firma de la passkey de `pk` no se registra nada
+        env.set_auths(&[]);
+        assert!(factory.try_deploy(&id, &pk).is_err());
+        assert!(factory.try_deploy_if_absent(&id, &pk).is_err());
+
+        // La firma de otra cuenta tampoco sirve
+        let other = factory.get_address(&id, &Bytes::from_array(&env, &public_key(8)));
+        env.mock_auths(&[MockAuth {
+            address: &other,
+            invoke: &MockAuthInvoke {
+                contract: &factory.address,
+                fn_name: "deploy",
+                args: (id.clone(), pk.clone()).into_val(&env),
+                sub_invokes: &[],
+            },
+        }]);
+        assert!(factory.try_deploy(&id, &pk).is_err());
+
+        assert_eq!(factory.lookup_by_credential(&id), None);
+        assert_eq!(
+            factory.lookup_by_key(&BytesN::from_array(&env, &public_key(7))),
+            None
+        );
+
+        // Con la firma de la propia cuenta se despliega y se registra
+        env.mock_all_auths();
+        let address = factory.deploy(&id, &pk);
+        assert_eq!(
+            env.auths(),
+            std::vec![(
+                address.clone(),
+                AuthorizedInvocation {
+                    function: AuthorizedFunction::Contract((
+                        factory.address.clone(),
+                        Symbol::new(&env, "deploy"),
+                        (id.clone(), pk).into_val(&env),
+                    )),
+                    sub_invocations: std::vec![],
+                }
+            )]
+        );
+        assert_eq!(factory.lookup_by_credential(&id), Some(address));
+    }
+
+    #[test]
+    fn test_duplicate_credential() {
+        let env = Env::default();
+        let factory = setup(&env);
+        let id = credential_id(&env, 1);
+        let pk = Bytes::from_array(&env, &public_key(7));
+        let address = factory.deploy(&id, &pk);
+
+        // Otra clave no puede reclamar un credential id ya registrado: su
+        // salt lleva a otra dirección y el credential id ya tiene cuenta
+        let other = Bytes::from_array(&env, &public_key(8));
+        assert_ne!(factory.get_address(&id, &other), address);
+        assert_eq!(
+            factory.try_deploy(&id, &other),
+            Err(Ok(Error::CredentialExists))
+        );
+        assert_eq!(
+            factory.try_deploy_if_absent(&id, &other),
+            Err(Ok(Error::CredentialExists))
+        );
+
+        assert_eq!(factory.lookup_by_credential(&id), Some(address.clone()));
+        assert_eq!(
+            factory.lookup_by_key(&BytesN::from_array(&env, &public_key(8))),
+            None
+        );
+        let account = WebAuthnAccountClient::new(&env, &address);
+        assert_eq!(
+            account.get_public_key(),
+            BytesN::from_array(&env, &public_key(7))
+        );
+    }
+
+    #[test]
+    fn test_duplicate_key() {
+        let env = Env::default();
+        let factory = setup(&env);
+        let pk = Bytes::from_array(&env, &public_key(7));
+        let address = factory.deploy(&credential_id(&env, 1), &pk);
+
+        assert_eq!(
+            factory.try_deploy(&credential_id(&env, 2), &pk),
+            Err(Ok(Error::KeyExists))
+        );
+        assert_eq!(factory.lookup_by_credential(&credential_id(&env, 2)), None);
+        assert_eq!(
+            factory.lookup_by_key(&BytesN::from_array(&env, &public_key(7))),
+            Some(address)
+        );
+    }
+}
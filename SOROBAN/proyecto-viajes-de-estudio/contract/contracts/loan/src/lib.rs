@@ -1,7 +1,13 @@
+//! EBAS Credit Scoring Loan Contract
+
+#![no_std]
+
+pub mod types;
+
 use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env, Symbol, Vec};
 
 // Import types directly in this module
-use crate::loan_types::{LoanConfig, LoanError, LoanRecord, TransferResult};
+use crate::types::{LoanConfig, LoanError, LoanRecord, TransferResult};
 
 // Storage keys
 const CONFIG_KEY: Symbol = symbol_short!("CONFIG");
@@ -13,13 +19,13 @@ pub struct LoanContract;
 #[contractimpl]
 impl LoanContract {
     /// Initialize the loan contract with admin, token, and pool addresses
-    /// 
+    ///
     /// # Arguments
     /// * `admin` - Address of the contract administrator
     /// * `token_address` - Address of the USDC token contract
     /// * `pool_address` - Address holding the loan pool funds
     /// * `min_credit_score` - Minimum credit score required (default: 700)
-    /// 
+    ///
     /// # Returns
     /// Result indicating success or error
     pub fn initialize(
@@ -35,7 +41,7 @@ impl LoanContract {
         }
 
         // Validate minimum credit score (should be between 500-850)
-        if min_credit_score < 500 || min_credit_score > 850 {
+        if !(500..=850).contains(&min_credit_score) {
             return Err(LoanError::InvalidAmount);
         }
 
@@ -49,7 +55,7 @@ impl LoanContract {
         };
 
         env.storage().instance().set(&CONFIG_KEY, &config);
-        
+
         // Initialize pool balance to 0
         env.storage().instance().set(&POOL_BAL, &0i128);
 
@@ -57,15 +63,15 @@ impl LoanContract {
     }
 
     /// Transfer a loan to a recipient based on their credit score
-    /// 
+    ///
     /// # Arguments
     /// * `recipient` - Address to receive the loan
     /// * `amount` - Amount in stroops (with 7 decimals)
     /// * `credit_score` - Credit score of the recipient (500-850)
-    /// 
+    ///
     /// # Returns
     /// TransferResult with details of the disbursement
-    /// 
+    ///
     /// # Errors
     /// * InsufficientCreditScore - if score < min_credit_score
     /// * InsufficientPoolFunds - if pool doesn't have enough balance
@@ -137,10 +143,10 @@ impl LoanContract {
     }
 
     /// Get loan history for a specific user
-    /// 
+    ///
     /// # Arguments
     /// * `user` - Address to query loan history for
-    /// 
+    ///
     /// # Returns
     /// Vector of LoanRecord entries
     pub fn get_loan_history(env: Env, user: Address) -> Vec<LoanRecord> {
@@ -152,22 +158,18 @@ impl LoanContract {
     }
 
     /// Deposit funds into the loan pool
-    /// 
+    ///
     /// # Arguments
     /// * `admin` - Admin address (must match configured admin)
     /// * `amount` - Amount to deposit
-    /// 
+    ///
     /// # Returns
     /// New pool balance after deposit
-    /// 
+    ///
     /// # Errors
     /// * Unauthorized - if caller is not admin
     /// * InvalidAmount - if amount <= 0
-    pub fn deposit_to_pool(
-        env: Env,
-        admin: Address,
-        amount: i128,
-    ) -> Result<i128, LoanError> {
+    pub fn deposit_to_pool(env: Env, admin: Address, amount: i128) -> Result<i128, LoanError> {
         // Validate admin
         Self::require_admin(&env, &admin)?;
 
@@ -178,7 +180,7 @@ impl LoanContract {
 
         // Get current balance
         let current_balance: i128 = env.storage().instance().get(&POOL_BAL).unwrap_or(0);
-        
+
         // Add deposit
         let new_balance = current_balance + amount;
         env.storage().instance().set(&POOL_BAL, &new_balance);
@@ -190,7 +192,7 @@ impl LoanContract {
     }
 
     /// Get current pool balance
-    /// 
+    ///
     /// # Returns
     /// Current balance in the pool
     pub fn get_pool_balance(env: Env) -> i128 {
@@ -198,20 +200,15 @@ impl LoanContract {
     }
 
     /// Check if a user is eligible for a loan
-    /// 
+    ///
     /// # Arguments
     /// * `user` - Address to check eligibility
     /// * `amount` - Requested loan amount
     /// * `credit_score` - User's credit score
-    /// 
+    ///
     /// # Returns
     /// true if eligible, false otherwise
-    pub fn check_eligibility(
-        env: Env,
-        user: Address,
-        amount: i128,
-        credit_score: u32,
-    ) -> bool {
+    pub fn check_eligibility(env: Env, user: Address, amount: i128, credit_score: u32) -> bool {
         // Get config (return false if not initialized)
         let config = match Self::require_initialized(&env) {
             Ok(c) => c,
@@ -265,11 +262,11 @@ impl LoanContract {
     /// Check if user has an active loan (within last 24 hours)
     fn has_active_loan(env: &Env, user: &Address) -> bool {
         let key = (symbol_short!("LAST_LOAN"), user.clone());
-        
+
         if let Some(last_timestamp) = env.storage().instance().get::<(Symbol, Address), u64>(&key) {
             let current_time = env.ledger().timestamp();
             let time_diff = current_time.saturating_sub(last_timestamp);
-            
+
             // 24 hours = 86400 seconds
             time_diff < 86400
         } else {
@@ -280,7 +277,7 @@ impl LoanContract {
     /// Record a loan in the history
     fn record_loan(env: &Env, loan: LoanRecord) {
         let user = loan.recipient.clone();
-        
+
         // Store the loan in history (append to vector)
         let history_key = (symbol_short!("HISTORY"), user.clone());
         let mut history: Vec<LoanRecord> = env
@@ -288,13 +285,15 @@ impl LoanContract {
             .instance()
             .get(&history_key)
             .unwrap_or(Vec::new(env));
-        
+
         history.push_back(loan.clone());
         env.storage().instance().set(&history_key, &history);
-        
+
         // Update last loan timestamp
         let timestamp_key = (symbol_short!("LAST_LOAN"), user);
-        env.storage().instance().set(&timestamp_key, &loan.timestamp);
+        env.storage()
+            .instance()
+            .set(&timestamp_key, &loan.timestamp);
     }
 }
 
@@ -316,7 +315,7 @@ mod loan_test {
 
         // Initialize should succeed
         client.initialize(&admin, &token, &pool, &min_score);
-        
+
         // Verify pool balance is 0 initially
         let balance = client.get_pool_balance();
         assert_eq!(balance, 0);
@@ -332,13 +331,13 @@ mod loan_test {
         let token = Address::generate(&env);
         let pool = Address::generate(&env);
         let recipient = Address::generate(&env);
-        
+
         // Initialize contract
         client.initialize(&admin, &token, &pool, &700);
-        
+
         // Deposit funds to pool
         client.deposit_to_pool(&admin, &10_000_000_000);
-        
+
         let amount = 5_000_000_000; // 500 USDC (with 7 decimals)
         let credit_score = 750;
 
@@ -346,7 +345,7 @@ mod loan_test {
         let result = client.transfer_loan(&recipient, &amount, &credit_score);
         assert!(result.success);
         assert_eq!(result.amount, amount);
-        
+
         // Check pool balance decreased
         let balance = client.get_pool_balance();
         assert_eq!(balance, 5_000_000_000);
@@ -363,10 +362,10 @@ mod loan_test {
         let token = Address::generate(&env);
         let pool = Address::generate(&env);
         let recipient = Address::generate(&env);
-        
+
         client.initialize(&admin, &token, &pool, &700);
         client.deposit_to_pool(&admin, &10_000_000_000);
-        
+
         let amount = 5_000_000_000;
         let credit_score = 650;
 
@@ -383,15 +382,15 @@ mod loan_test {
         let token = Address::generate(&env);
         let pool = Address::generate(&env);
         let user = Address::generate(&env);
-        
+
         client.initialize(&admin, &token, &pool, &700);
-        
+
         let history = client.get_loan_history(&user);
         assert_eq!(history.len(), 0);
-        
+
         client.deposit_to_pool(&admin, &10_000_000_000);
         client.transfer_loan(&user, &5_000_000_000, &750);
-        
+
         let history = client.get_loan_history(&user);
         assert_eq!(history.len(), 1);
     }
@@ -405,9 +404,9 @@ mod loan_test {
         let admin = Address::generate(&env);
         let token = Address::generate(&env);
         let pool = Address::generate(&env);
-        
+
         client.initialize(&admin, &token, &pool, &700);
-        
+
         let amount = 100_000_000_000;
         let new_balance = client.deposit_to_pool(&admin, &amount);
         assert_eq!(new_balance, amount);
@@ -423,12 +422,12 @@ mod loan_test {
         let token = Address::generate(&env);
         let pool = Address::generate(&env);
         let user = Address::generate(&env);
-        
+
         client.initialize(&admin, &token, &pool, &700);
         client.deposit_to_pool(&admin, &10_000_000_000);
-        
+
         let amount = 5_000_000_000;
-        
+
         let eligible = client.check_eligibility(&user, &amount, &750);
         assert!(eligible);
 
@@ -447,10 +446,10 @@ mod loan_test {
         let token = Address::generate(&env);
         let pool = Address::generate(&env);
         let recipient = Address::generate(&env);
-        
+
         client.initialize(&admin, &token, &pool, &700);
         client.deposit_to_pool(&admin, &20_000_000_000);
-        
+
         let amount = 5_000_000_000;
         let credit_score = 750;
 
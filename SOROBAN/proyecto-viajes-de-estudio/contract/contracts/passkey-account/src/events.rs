@@ -0,0 +1,181 @@
+//! Events published by the passkey account
+//!
+//! Every event has two symbol topics: the subject (`account`, `signer`,
+//! `auth`, ...) and what happened to it. These topics are stable so that
+//! off-chain indexers can rebuild the history of an account.
+
+use soroban_sdk::{symbol_short, Address, Bytes, BytesN, Env, Symbol};
+
+const ACCOUNT: Symbol = symbol_short!("account");
+const SIGNER: Symbol = symbol_short!("signer");
+const THRESHOLD: Symbol = symbol_short!("threshold");
+const OWNER: Symbol = symbol_short!("owner");
+const SESSION: Symbol = symbol_short!("session");
+const GUARDIANS: Symbol = symbol_short!("guardians");
+const RECOVERY: Symbol = symbol_short!("recovery");
+const ROTATION: Symbol = symbol_short!("rotation");
+const POLICY: Symbol = symbol_short!("policy");
+const CHANGE: Symbol = symbol_short!("change");
+const AUTH: Symbol = symbol_short!("auth");
+
+/// `(account, init)` - data: credential ID of the first passkey
+pub fn account_initialized(env: &Env, credential_id: &Bytes) {
+    env.events()
+        .publish((ACCOUNT, symbol_short!("init")), credential_id.clone());
+}
+
+/// `(account, upgraded)` - data: hash of the new WASM
+pub fn account_upgraded(env: &Env, wasm_hash: &BytesN<32>) {
+    env.events()
+        .publish((ACCOUNT, symbol_short!("upgraded")), wasm_hash.clone());
+}
+
+/// `(account, migrated)` - data: (previous schema version, new schema version)
+pub fn account_migrated(env: &Env, from: u32, to: u32) {
+    env.events()
+        .publish((ACCOUNT, symbol_short!("migrated")), (from, to));
+}
+
+/// `(signer, added)` - data: (credential ID, weight)
+pub fn signer_added(env: &Env, credential_id: &Bytes, weight: u32) {
+    env.events().publish(
+        (SIGNER, symbol_short!("added")),
+        (credential_id.clone(), weight),
+    );
+}
+
+/// `(signer, removed)` - data: credential ID
+pub fn signer_removed(env: &Env, credential_id: &Bytes) {
+    env.events()
+        .publish((SIGNER, symbol_short!("removed")), credential_id.clone());
+}
+
+/// `(threshold, changed)` - data: new threshold
+pub fn threshold_changed(env: &Env, threshold: u32) {
+    env.events()
+        .publish((THRESHOLD, symbol_short!("changed")), threshold);
+}
+
+/// `(owner, changed)` - data: credential ID of the new only passkey
+pub fn owner_changed(env: &Env, credential_id: &Bytes) {
+    env.events()
+        .publish((OWNER, symbol_short!("changed")), credential_id.clone());
+}
+
+/// `(session, added)` - data: (session key ID, expiry timestamp)
+pub fn session_key_added(env: &Env, id: &Bytes, expires_at: u64) {
+    env.events()
+        .publish((SESSION, symbol_short!("added")), (id.clone(), expires_at));
+}
+
+/// `(session, revoked)` - data: session key ID
+pub fn session_key_revoked(env: &Env, id: &Bytes) {
+    env.events()
+        .publish((SESSION, symbol_short!("revoked")), id.clone());
+}
+
+/// `(policy, limit_set)` - data: (token, daily limit, weekly limit)
+pub fn spending_limit_set(env: &Env, token: &Address, daily: i128, weekly: i128) {
+    env.events().publish(
+        (POLICY, symbol_short!("limit_set")),
+        (token.clone(), daily, weekly),
+    );
+}
+
+/// `(policy, limit_del)` - data: token
+pub fn spending_limit_removed(env: &Env, token: &Address) {
+    env.events()
+        .publish((POLICY, symbol_short!("limit_del")), token.clone());
+}
+
+/// `(policy, calls_set)` - data: (number of allowed contracts, number of denied contracts)
+pub fn call_policy_set(env: &Env, allowed: u32, denied: u32) {
+    env.events()
+        .publish((POLICY, symbol_short!("calls_set")), (allowed, denied));
+}
+
+/// `(policy, calls_del)` - data: none
+pub fn call_policy_removed(env: &Env) {
+    env.events()
+        .publish((POLICY, symbol_short!("calls_del")), ());
+}
+
+/// `(policy, rot_delay)` - data: new owner rotation delay in seconds
+pub fn rotation_delay_changed(env: &Env, delay: u64) {
+    env.events()
+        .publish((POLICY, symbol_short!("rot_delay")), delay);
+}
+
+/// `(guardians, changed)` - data: (number of guardians, quorum)
+pub fn guardians_changed(env: &Env, guardians: u32, quorum: u32) {
+    env.events()
+        .publish((GUARDIANS, symbol_short!("changed")), (guardians, quorum));
+}
+
+/// `(recovery, started)` - data: (credential ID of the new passkey, effective timestamp)
+pub fn recovery_started(env: &Env, credential_id: &Bytes, effective_at: u64) {
+    env.events().publish(
+        (RECOVERY, symbol_short!("started")),
+        (credential_id.clone(), effective_at),
+    );
+}
+
+/// `(recovery, cancelled)` - data: credential ID of the new passkey
+pub fn recovery_cancelled(env: &Env, credential_id: &Bytes) {
+    env.events().publish(
+        (RECOVERY, symbol_short!("cancelled")),
+        credential_id.clone(),
+    );
+}
+
+/// `(recovery, finished)` - data: credential ID of the new passkey
+pub fn recovery_finished(env: &Env, credential_id: &Bytes) {
+    env.events()
+        .publish((RECOVERY, symbol_short!("finished")), credential_id.clone());
+}
+
+/// `(rotation, proposed)` - data: (credential ID of the new passkey, effective timestamp)
+pub fn rotation_proposed(env: &Env, credential_id: &Bytes, effective_at: u64) {
+    env.events().publish(
+        (ROTATION, symbol_short!("proposed")),
+        (credential_id.clone(), effective_at),
+    );
+}
+
+/// `(rotation, cancelled)` - data: credential ID of the new passkey
+pub fn rotation_cancelled(env: &Env, credential_id: &Bytes) {
+    env.events().publish(
+        (ROTATION, symbol_short!("cancelled")),
+        credential_id.clone(),
+    );
+}
+
+/// `(rotation, applied)` - data: credential ID of the new passkey
+pub fn rotation_applied(env: &Env, credential_id: &Bytes) {
+    env.events()
+        .publish((ROTATION, symbol_short!("applied")), credential_id.clone());
+}
+
+/// `(auth, used)` - data: credential ID (or session key ID) of a signer whose
+/// signature authorized a call
+pub fn auth_used(env: &Env, credential_id: &Bytes) {
+    env.events()
+        .publish((AUTH, symbol_short!("used")), credential_id.clone());
+}
+
+/// `(change, proposed)` - data: (change ID, effective timestamp)
+pub fn change_proposed(env: &Env, id: u32, effective_at: u64) {
+    env.events()
+        .publish((CHANGE, symbol_short!("proposed")), (id, effective_at));
+}
+
+/// `(change, cancelled)` - data: change ID
+pub fn change_cancelled(env: &Env, id: u32) {
+    env.events()
+        .publish((CHANGE, symbol_short!("cancelled")), id);
+}
+
+/// `(change, applied)` - data: change ID
+pub fn change_applied(env: &Env, id: u32) {
+    env.events().publish((CHANGE, symbol_short!("applied")), id);
+}
@@ -0,0 +1,1567 @@
+//! Passkey Account Contract for Soroban
+//!
+//! This contract implements a custom account that uses secp256r1 (P-256) signatures
+//! for authentication, enabling WebAuthn/Passkey integration.
+//!
+//! Features:
+//! - WebAuthn compatible (secp256r1/ES256 signatures)
+//! - Biometric authentication (Face ID, Touch ID, Windows Hello)
+//! - No passwords required
+//! - Implements Soroban's CustomAccountInterface
+//!
+//! Account changes wait for the rotation delay, like a key rotation, so a
+//! compromised signer cannot take over the account before its owner can react.
+//! `add_signer`, `remove_signer`, `set_threshold`, `set_guardians`,
+//! `set_rotation_delay`, `upgrade`, `remove_spending_limit`,
+//! `remove_call_policy`, and `set_spending_limit` or `set_call_policy` when
+//! they loosen the current limit or policy only validate and queue the change
+//! and return its ID instead of applying it. Anyone can call
+//! `apply_change` once the delay has passed, and the account can
+//! `cancel_change` before that.
+
+#![no_std]
+
+mod events;
+
+use passkey_types::{ecdsa, public_key};
+use soroban_sdk::{
+    auth::{Context, CustomAccountInterface},
+    contract, contracterror, contractimpl, contracttype,
+    crypto::Hash,
+    symbol_short, Address, Bytes, BytesN, Env, Map, Symbol, TryFromVal, Vec,
+};
+
+/// The main contract struct
+#[contract]
+pub struct PasskeyAccount;
+
+/// Storage keys for the contract
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    /// Legacy (schema version 1): the single owner's secp256r1 public key
+    Owner,
+    /// Legacy (schema version 1): the owner's optional credential ID
+    CredentialId,
+    /// Storage schema version
+    Version,
+    /// Registered passkeys: credential ID -> `Signer`
+    Signers,
+    /// Total signer weight required to authorize
+    Threshold,
+    /// Session key registered under the given ID during a signer epoch (persistent storage)
+    SessionKey(u32, Bytes),
+    /// Spending limits for the given token contract (persistent storage)
+    SpendingLimit(Address),
+    /// Recent spends of the given token contract (persistent storage)
+    Spends(Address),
+    /// Guardians allowed to recover the account
+    Guardians,
+    /// Recovery started by the guardians and waiting for its timelock
+    Recovery,
+    /// Seconds between proposing a new owner passkey and applying it
+    RotationDelay,
+    /// Owner rotation proposed by the account and waiting for its timelock
+    Rotation,
+    /// Contracts and functions the account may authorize calls to
+    CallPolicy,
+    /// Counter bumped whenever the signers are reset, so that session keys
+    /// registered before a recovery or rotation stop working
+    SignerEpoch,
+    /// Account changes waiting for the rotation delay: change ID -> `PendingChange`
+    Changes,
+    /// ID of the next proposed account change
+    NextChangeId,
+}
+
+/// Error codes for the contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    /// Owner is already set
+    AlreadyInitialized = 1,
+    /// No owner set
+    NotInitialized = 2,
+    /// Invalid public key format
+    InvalidPublicKey = 3,
+    /// Signature verification failed
+    InvalidSignature = 4,
+    /// No passkey registered with the given credential ID
+    SignerNotFound = 5,
+    /// A passkey with the given credential ID is already registered
+    SignerAlreadyExists = 6,
+    /// The last remaining passkey cannot be removed
+    LastSigner = 7,
+    /// Signer weights must be greater than zero
+    InvalidWeight = 8,
+    /// The threshold must be between 1 and the total signer weight
+    InvalidThreshold = 9,
+    /// The signatures do not add up to the threshold
+    ThresholdNotMet = 10,
+    /// The same passkey signed more than once
+    DuplicateSigner = 11,
+    /// The session key is past its expiry timestamp
+    SessionKeyExpired = 12,
+    /// The session key is not allowed to authorize this call
+    SessionKeyNotAllowed = 13,
+    /// The session key expiry or scope is invalid
+    InvalidSessionKey = 14,
+    /// The token spending limit would be exceeded without an additional signer
+    SpendingLimitExceeded = 15,
+    /// Spending limits must be positive and the daily limit cannot exceed the weekly one
+    InvalidSpendingLimit = 16,
+    /// Guardians must be distinct, exclude the account and have a reachable quorum
+    InvalidGuardians = 17,
+    /// The address is not one of the account's guardians
+    NotGuardian = 18,
+    /// Fewer guardians than the quorum approved the recovery
+    GuardianQuorumNotMet = 19,
+    /// There is no recovery in progress
+    RecoveryNotPending = 20,
+    /// The recovery timelock has not expired yet
+    RecoveryTimelocked = 21,
+    /// A recovery is already in progress
+    RecoveryAlreadyPending = 22,
+    /// There is no key rotation in progress
+    RotationNotPending = 23,
+    /// The key rotation timelock has not expired yet
+    RotationTimelocked = 24,
+    /// A key rotation is already in progress
+    RotationAlreadyPending = 25,
+    /// The rotation delay is below the minimum
+    InvalidRotationDelay = 26,
+    /// The call policy does not allow authorizing this call
+    CallNotAllowed = 27,
+    /// The signer's policy does not allow it to authorize this call
+    SignerNotAllowed = 28,
+    /// The stored schema version is newer than this contract code
+    UnsupportedVersion = 29,
+    /// The secp256r1 signature is not a valid DER or r || s encoding
+    Secp256r1SignatureParse = 30,
+    /// The public key is already registered under another credential ID
+    KeyAlreadyRegistered = 31,
+    /// There is no pending account change with the given ID
+    ChangeNotPending = 32,
+    /// The account change's timelock has not expired yet
+    ChangeTimelocked = 33,
+}
+
+/// A passkey or backup key registered on the account
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Signer {
+    /// Public key the signer's signatures are verified against
+    pub key: SignerKey,
+    /// Weight counted towards the threshold when this signer signs
+    pub weight: u32,
+    /// Account functions this signer may authorize (any call if empty)
+    pub functions: Vec<Symbol>,
+}
+
+/// A public key, tagged with the signature scheme it verifies
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SignerKey {
+    /// secp256r1 public key (64 bytes uncompressed: 32 bytes X + 32 bytes Y)
+    Secp256r1(BytesN<64>),
+    /// ed25519 public key
+    Ed25519(BytesN<32>),
+}
+
+/// A short-lived key that can authorize a limited set of calls without the
+/// owner's passkeys
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionKey {
+    /// Key that signs on behalf of the account
+    pub key: SignerKey,
+    /// Ledger timestamp after which the key is no longer valid
+    pub expires_at: u64,
+    /// Contracts the key may authorize calls to
+    pub contracts: Vec<Address>,
+    /// Functions the key may authorize on those contracts
+    pub functions: Vec<Symbol>,
+}
+
+/// Maximum amount of a token the account may spend in a rolling window
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SpendingLimit {
+    /// Maximum amount spent in the last 24 hours
+    pub daily: i128,
+    /// Maximum amount spent in the last 7 days
+    pub weekly: i128,
+}
+
+/// An amount of a token spent at a given ledger timestamp
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Spend {
+    /// Ledger timestamp of the authorization
+    pub timestamp: u64,
+    /// Amount transferred or approved
+    pub amount: i128,
+}
+
+/// Guardians that can jointly recover the account if its passkeys are lost
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GuardianConfig {
+    /// Addresses of the guardians (e.g. parents, the school)
+    pub guardians: Vec<Address>,
+    /// Number of guardians that must approve a recovery
+    pub quorum: u32,
+    /// Seconds the owner has to cancel a recovery before it can be finalized
+    pub delay: u64,
+}
+
+/// A new owner passkey waiting for its timelock (key rotation or recovery)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingOwner {
+    /// Credential ID of the new passkey
+    pub credential_id: Bytes,
+    /// secp256r1 public key of the new passkey
+    pub public_key: BytesN<64>,
+    /// Ledger timestamp from which the recovery can be finalized
+    pub effective_at: u64,
+}
+
+/// A change to the account's signers, guardians or code
+///
+/// These changes wait for the rotation delay like an owner rotation does, so
+/// a stolen passkey cannot take over the account faster than by proposing a
+/// new owner.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AccountChange {
+    /// Register a signer under a credential ID
+    AddSigner(Bytes, Signer),
+    /// Remove the signer registered under a credential ID
+    RemoveSigner(Bytes),
+    /// Set the total signer weight required to authorize
+    SetThreshold(u32),
+    /// Replace the guardian configuration
+    SetGuardians(GuardianConfig),
+    /// Set the delay of owner rotations and account changes (seconds)
+    SetRotationDelay(u64),
+    /// Replace the account's contract code with an already uploaded WASM
+    Upgrade(BytesN<32>),
+    /// Set the spending limit of a token
+    SetSpendingLimit(Address, SpendingLimit),
+    /// Remove the spending limit of a token
+    RemoveSpendingLimit(Address),
+    /// Set the call policy
+    SetCallPolicy(CallPolicy),
+    /// Remove the call policy
+    RemoveCallPolicy,
+}
+
+/// An account change waiting for its timelock
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingChange {
+    /// The change to apply
+    pub change: AccountChange,
+    /// Ledger timestamp from which the change can be applied
+    pub effective_at: u64,
+}
+
+/// Contracts and functions the account may authorize calls to
+///
+/// Once a policy is set, every call that is not allow-listed is rejected.
+/// Calls to the account itself are always allowed so the policy can be changed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CallPolicy {
+    /// Allowed contracts -> allowed functions (all functions if empty)
+    pub allowed: Map<Address, Vec<Symbol>>,
+    /// Contracts that are rejected even if allow-listed
+    pub denied: Vec<Address>,
+}
+
+/// Storage schema version written by this contract code
+///
+/// 1. Single owner key under `DataKey::Owner` / `DataKey::CredentialId`
+/// 2. Signer map with weights and threshold
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Rotation delay used until the account configures its own (seconds)
+const DEFAULT_ROTATION_DELAY: u64 = 24 * 60 * 60;
+/// Shortest rotation delay an account can configure (seconds)
+const MIN_ROTATION_DELAY: u64 = 60 * 60;
+
+/// Length of the daily spending window (seconds)
+const DAY: u64 = 24 * 60 * 60;
+/// Length of the weekly spending window (seconds)
+const WEEK: u64 = 7 * DAY;
+
+/// A signature, tagged with the scheme that produced it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Signature {
+    /// secp256r1 signature from a passkey, DER-encoded as returned by the
+    /// browser or raw r || s (64 bytes)
+    Secp256r1(Bytes),
+    /// ed25519 signature from a Stellar keypair
+    Ed25519(BytesN<64>),
+}
+
+/// A signature produced by one of the account's signers or session keys
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SignerSignature {
+    /// Credential ID of the signer (or ID of the session key) that signed
+    pub credential_id: Bytes,
+    /// The signature over the payload
+    pub signature: Signature,
+}
+
+#[contractimpl]
+impl PasskeyAccount {
+    /// Initialize the contract with a secp256r1 public key from a passkey
+    ///
+    /// The passkey is registered with weight 1 and the threshold is set to 1.
+    ///
+    /// # Arguments
+    /// * `public_key` - secp256r1 public key, either compressed SEC1 (33 bytes),
+    ///   uncompressed SEC1 (65 bytes), raw X || Y coordinates (64 bytes) or the
+    ///   COSE_Key from the registration's `attestedCredentialData`
+    /// * `credential_id` - Credential ID from WebAuthn, used to select the key in `__check_auth`
+    ///
+    /// # Errors
+    /// * `AlreadyInitialized` - If the contract is already initialized
+    /// * `InvalidPublicKey` - If the public key is malformed, not an ES256 COSE_Key
+    ///   or not on the P-256 curve
+    pub fn init(env: Env, public_key: Bytes, credential_id: Bytes) -> Result<(), Error> {
+        // Check if already initialized
+        if env.storage().instance().has(&DataKey::Signers)
+            || env.storage().instance().has(&DataKey::Owner)
+        {
+            return Err(Error::AlreadyInitialized);
+        }
+        let public_key = normalize_public_key(&env, &public_key)?;
+
+        // Store the passkey as the only signer
+        Self::reset_signers(&env, credential_id.clone(), public_key);
+        env.storage()
+            .instance()
+            .set(&DataKey::Version, &SCHEMA_VERSION);
+        events::account_initialized(&env, &credential_id);
+
+        Ok(())
+    }
+
+    /// Get the storage schema version of the account
+    pub fn get_version(env: Env) -> u32 {
+        // Accounts created before the version was stored use the legacy layout
+        env.storage().instance().get(&DataKey::Version).unwrap_or(1)
+    }
+
+    /// Propose replacing the account's contract code (requires the account's auth)
+    ///
+    /// The new code takes effect after `apply_change`; call `migrate`
+    /// afterwards to convert the storage to the new code's schema.
+    ///
+    /// # Arguments
+    /// * `new_wasm_hash` - Hash of the already uploaded WASM
+    ///
+    /// # Returns
+    /// The ID of the pending change
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<u32, Error> {
+        Self::get_signers(&env)?;
+        env.current_contract_address().require_auth();
+
+        Self::propose_change(&env, AccountChange::Upgrade(new_wasm_hash))
+    }
+
+    /// Convert the storage from an older schema version to `SCHEMA_VERSION`
+    ///
+    /// Migrations only rewrite the existing state into the new layout, so
+    /// anyone can call this. Calling it on an up-to-date account does nothing.
+    ///
+    /// # Returns
+    /// The schema version after the migration
+    ///
+    /// # Errors
+    /// * `NotInitialized` - If the account has no owner to migrate
+    /// * `UnsupportedVersion` - If the storage was written by newer contract code
+    pub fn migrate(env: Env) -> Result<u32, Error> {
+        let version = Self::get_version(env.clone());
+        if version > SCHEMA_VERSION {
+            return Err(Error::UnsupportedVersion);
+        }
+
+        if version == 1 {
+            // Single owner -> signer map with the owner as the only signer
+            let public_key: BytesN<64> = env
+                .storage()
+                .instance()
+                .get(&DataKey::Owner)
+                .ok_or(Error::NotInitialized)?;
+            let credential_id: Bytes = env
+                .storage()
+                .instance()
+                .get(&DataKey::CredentialId)
+                .unwrap_or(Bytes::new(&env));
+
+            Self::reset_signers(&env, credential_id, public_key);
+            env.storage().instance().remove(&DataKey::Owner);
+            env.storage().instance().remove(&DataKey::CredentialId);
+        }
+
+        if version < SCHEMA_VERSION {
+            env.storage()
+                .instance()
+                .set(&DataKey::Version, &SCHEMA_VERSION);
+            events::account_migrated(&env, version, SCHEMA_VERSION);
+        }
+
+        Ok(SCHEMA_VERSION)
+    }
+
+    /// Get all registered passkeys (credential ID -> signer)
+    pub fn list_signers(env: Env) -> Result<Map<Bytes, Signer>, Error> {
+        Self::get_signers(&env)
+    }
+
+    /// Get the total signer weight required to authorize
+    pub fn get_threshold(env: Env) -> Result<u32, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Threshold)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Propose registering an additional passkey or backup key
+    /// (requires the account's auth)
+    ///
+    /// The signer is added by `apply_change` once the rotation delay has passed.
+    ///
+    /// # Arguments
+    /// * `credential_id` - ID used to select the signer in `__check_auth`
+    /// * `key` - secp256r1 passkey or ed25519 Stellar public key
+    /// * `weight` - Weight counted towards the threshold
+    /// * `functions` - Account functions the signer may authorize (any call if empty),
+    ///   e.g. only `propose_owner` for a backup key
+    ///
+    /// # Errors
+    /// * `SignerAlreadyExists` - If the credential ID is already registered
+    /// * `KeyAlreadyRegistered` - If the key is registered under another credential ID
+    /// * `InvalidWeight` - If the weight is zero
+    /// * `InvalidPublicKey` - If a secp256r1 key is not on the P-256 curve
+    ///
+    /// # Returns
+    /// The ID of the pending change
+    pub fn add_signer(
+        env: Env,
+        credential_id: Bytes,
+        key: SignerKey,
+        weight: u32,
+        functions: Vec<Symbol>,
+    ) -> Result<u32, Error> {
+        Self::get_signers(&env)?;
+        env.current_contract_address().require_auth();
+
+        let signer = Signer {
+            key,
+            weight,
+            functions,
+        };
+        Self::propose_change(&env, AccountChange::AddSigner(credential_id, signer))
+    }
+
+    /// Propose removing a registered passkey (requires the account's auth)
+    ///
+    /// The signer is removed by `apply_change` once the rotation delay has passed.
+    ///
+    /// # Errors
+    /// * `SignerNotFound` - If the credential ID is not registered
+    /// * `LastSigner` - If it is the only remaining passkey
+    /// * `InvalidThreshold` - If the remaining passkeys could not reach the threshold
+    ///
+    /// # Returns
+    /// The ID of the pending change
+    pub fn remove_signer(env: Env, credential_id: Bytes) -> Result<u32, Error> {
+        Self::get_signers(&env)?;
+        env.current_contract_address().require_auth();
+
+        Self::propose_change(&env, AccountChange::RemoveSigner(credential_id))
+    }
+
+    /// Propose a new total signer weight required to authorize
+    /// (requires the account's auth)
+    ///
+    /// The threshold changes with `apply_change` once the rotation delay has passed.
+    ///
+    /// # Errors
+    /// * `InvalidThreshold` - If the threshold is zero or above the total signer weight
+    ///
+    /// # Returns
+    /// The ID of the pending change
+    pub fn set_threshold(env: Env, threshold: u32) -> Result<u32, Error> {
+        Self::get_signers(&env)?;
+        env.current_contract_address().require_auth();
+
+        Self::propose_change(&env, AccountChange::SetThreshold(threshold))
+    }
+
+    /// Register a session key under `id` (requires the account's auth)
+    ///
+    /// # Errors
+    /// * `InvalidSessionKey` - If the key is already expired, has an empty scope
+    ///   or lists the account itself
+    /// * `InvalidPublicKey` - If a secp256r1 key is not on the P-256 curve
+    pub fn add_session_key(env: Env, id: Bytes, session_key: SessionKey) -> Result<(), Error> {
+        Self::get_signers(&env)?;
+        env.current_contract_address().require_auth();
+
+        if session_key.expires_at <= env.ledger().timestamp()
+            || session_key.contracts.is_empty()
+            || session_key.functions.is_empty()
+            || session_key
+                .contracts
+                .contains(env.current_contract_address())
+        {
+            return Err(Error::InvalidSessionKey);
+        }
+        validate_signer_key(&session_key.key)?;
+
+        let key = Self::session_key_entry(&env, id.clone());
+        env.storage().persistent().set(&key, &session_key);
+        Self::extend_persistent(&env, &key);
+        events::session_key_added(&env, &id, session_key.expires_at);
+
+        Ok(())
+    }
+
+    /// Revoke a session key before it expires (requires the account's auth)
+    pub fn revoke_session_key(env: Env, id: Bytes) -> Result<(), Error> {
+        Self::get_signers(&env)?;
+        env.current_contract_address().require_auth();
+
+        env.storage()
+            .persistent()
+            .remove(&Self::session_key_entry(&env, id.clone()));
+        events::session_key_revoked(&env, &id);
+
+        Ok(())
+    }
+
+    /// Get the session key registered under `id` (if any)
+    pub fn get_session_key(env: Env, id: Bytes) -> Option<SessionKey> {
+        let key = Self::session_key_entry(&env, id);
+        let session_key = env.storage().persistent().get(&key);
+        if session_key.is_some() {
+            Self::extend_persistent(&env, &key);
+        }
+        session_key
+    }
+
+    /// Limit how much of `token` the account can transfer or approve per day
+    /// and per week (requires the account's auth)
+    ///
+    /// Calls above the limit need signatures weighing more than the threshold.
+    /// A first or lower limit applies at once. Raising either limit loosens
+    /// it, so it is applied by `apply_change` once the rotation delay has passed.
+    ///
+    /// # Errors
+    /// * `InvalidSpendingLimit` - If a limit is not positive or `daily` exceeds `weekly`
+    ///
+    /// # Returns
+    /// The ID of the pending change if the limit is raised
+    pub fn set_spending_limit(
+        env: Env,
+        token: Address,
+        limit: SpendingLimit,
+    ) -> Result<Option<u32>, Error> {
+        Self::get_signers(&env)?;
+        env.current_contract_address().require_auth();
+
+        let tightens = match Self::get_spending_limit(env.clone(), token.clone()) {
+            Some(current) => limit.daily <= current.daily && limit.weekly <= current.weekly,
+            None => true,
+        };
+        Self::execute_or_propose(
+            &env,
+            AccountChange::SetSpendingLimit(token, limit),
+            tightens,
+        )
+    }
+
+    /// Propose removing the spending limit of `token` (requires the account's auth)
+    ///
+    /// The limit is removed by `apply_change` once the rotation delay has passed.
+    ///
+    /// # Returns
+    /// The ID of the pending change
+    pub fn remove_spending_limit(env: Env, token: Address) -> Result<u32, Error> {
+        Self::get_signers(&env)?;
+        env.current_contract_address().require_auth();
+
+        Self::propose_change(&env, AccountChange::RemoveSpendingLimit(token))
+    }
+
+    /// Restrict the contracts and functions the account may authorize calls to
+    /// (requires the account's auth)
+    ///
+    /// A first policy, or one that allows no call the current policy rejects,
+    /// applies at once. Any other policy loosens the current one, so it is
+    /// applied by `apply_change` once the rotation delay has passed.
+    ///
+    /// # Returns
+    /// The ID of the pending change if the policy is loosened
+    pub fn set_call_policy(env: Env, policy: CallPolicy) -> Result<Option<u32>, Error> {
+        Self::get_signers(&env)?;
+        env.current_contract_address().require_auth();
+
+        let tightens = match Self::get_call_policy(env.clone()) {
+            Some(current) => call_policy_tightens(&current, &policy),
+            None => true,
+        };
+        Self::execute_or_propose(&env, AccountChange::SetCallPolicy(policy), tightens)
+    }
+
+    /// Propose removing the call policy, allowing calls to any contract
+    /// (requires the account's auth)
+    ///
+    /// The policy is removed by `apply_change` once the rotation delay has passed.
+    ///
+    /// # Returns
+    /// The ID of the pending change
+    pub fn remove_call_policy(env: Env) -> Result<u32, Error> {
+        Self::get_signers(&env)?;
+        env.current_contract_address().require_auth();
+
+        Self::propose_change(&env, AccountChange::RemoveCallPolicy)
+    }
+
+    /// Get the call policy (if any)
+    pub fn get_call_policy(env: Env) -> Option<CallPolicy> {
+        env.storage().instance().get(&DataKey::CallPolicy)
+    }
+
+    /// Get the spending limit of `token` (if any)
+    pub fn get_spending_limit(env: Env, token: Address) -> Option<SpendingLimit> {
+        let key = DataKey::SpendingLimit(token);
+        let limit = env.storage().persistent().get(&key);
+        if limit.is_some() {
+            Self::extend_persistent(&env, &key);
+        }
+        limit
+    }
+
+    /// Get the amount of `token` spent in the last day and in the last week
+    pub fn get_spent(env: Env, token: Address) -> (i128, i128) {
+        let spends = Self::get_spends(&env, &token);
+        Self::spent_in_windows(&env, &spends)
+    }
+
+    /// Propose the guardians that can recover the account (requires the account's auth)
+    ///
+    /// The guardians change with `apply_change` once the rotation delay has passed.
+    ///
+    /// # Errors
+    /// * `InvalidGuardians` - If a guardian is repeated or is the account itself, or the
+    ///   quorum is zero or above the number of guardians
+    ///
+    /// # Returns
+    /// The ID of the pending change
+    pub fn set_guardians(env: Env, config: GuardianConfig) -> Result<u32, Error> {
+        Self::get_signers(&env)?;
+        env.current_contract_address().require_auth();
+
+        Self::propose_change(&env, AccountChange::SetGuardians(config))
+    }
+
+    /// Get the guardian configuration (if any)
+    pub fn get_guardians(env: Env) -> Option<GuardianConfig> {
+        let config = env.storage().instance().get(&DataKey::Guardians);
+        if config.is_some() {
+            Self::extend_instance(&env);
+        }
+        config
+    }
+
+    /// Start recovering the account to a new passkey
+    ///
+    /// Every address in `approvals` must be a guardian and authorize the call.
+    /// The recovery can be finalized once the timelock expires, unless the
+    /// current owner cancels it first.
+    ///
+    /// # Errors
+    /// * `NotGuardian` - If an approving address is not a guardian
+    /// * `GuardianQuorumNotMet` - If fewer distinct guardians than the quorum approve
+    /// * `RecoveryAlreadyPending` - If a recovery is already in progress
+    /// * `InvalidPublicKey` - If the new key is not on the P-256 curve
+    pub fn initiate_recovery(
+        env: Env,
+        approvals: Vec<Address>,
+        new_public_key: BytesN<64>,
+        new_credential_id: Bytes,
+    ) -> Result<(), Error> {
+        Self::get_signers(&env)?;
+        let config = Self::get_guardians(env.clone()).ok_or(Error::NotGuardian)?;
+        if env.storage().instance().has(&DataKey::Recovery) {
+            return Err(Error::RecoveryAlreadyPending);
+        }
+        let new_public_key = normalize_public_key(&env, &new_public_key.into())?;
+
+        let mut approved: Map<Address, ()> = Map::new(&env);
+        for guardian in approvals.iter() {
+            if !config.guardians.contains(guardian.clone()) {
+                return Err(Error::NotGuardian);
+            }
+            if !approved.contains_key(guardian.clone()) {
+                guardian.require_auth();
+                approved.set(guardian, ());
+            }
+        }
+        if approved.len() < config.quorum {
+            return Err(Error::GuardianQuorumNotMet);
+        }
+
+        let recovery = PendingOwner {
+            credential_id: new_credential_id,
+            public_key: new_public_key,
+            effective_at: env.ledger().timestamp().saturating_add(config.delay),
+        };
+        env.storage().instance().set(&DataKey::Recovery, &recovery);
+        Self::extend_instance(&env);
+        events::recovery_started(&env, &recovery.credential_id, recovery.effective_at);
+
+        Ok(())
+    }
+
+    /// Get the recovery in progress (if any)
+    pub fn get_recovery(env: Env) -> Option<PendingOwner> {
+        let recovery = env.storage().instance().get(&DataKey::Recovery);
+        if recovery.is_some() {
+            Self::extend_instance(&env);
+        }
+        recovery
+    }
+
+    /// Cancel the recovery in progress (requires the account's auth)
+    ///
+    /// # Errors
+    /// * `RecoveryNotPending` - If there is no recovery in progress
+    pub fn cancel_recovery(env: Env) -> Result<(), Error> {
+        Self::get_signers(&env)?;
+        env.current_contract_address().require_auth();
+
+        let recovery = Self::get_recovery(env.clone()).ok_or(Error::RecoveryNotPending)?;
+        env.storage().instance().remove(&DataKey::Recovery);
+        events::recovery_cancelled(&env, &recovery.credential_id);
+
+        Ok(())
+    }
+
+    /// Complete a recovery whose timelock has expired, making the new passkey
+    /// the only signer (callable by anyone)
+    ///
+    /// # Errors
+    /// * `RecoveryNotPending` - If there is no recovery in progress
+    /// * `RecoveryTimelocked` - If the timelock has not expired yet
+    pub fn finalize_recovery(env: Env) -> Result<(), Error> {
+        Self::get_signers(&env)?;
+
+        let recovery = Self::get_recovery(env.clone()).ok_or(Error::RecoveryNotPending)?;
+        if env.ledger().timestamp() < recovery.effective_at {
+            return Err(Error::RecoveryTimelocked);
+        }
+
+        // A rotation proposed with the lost passkeys must not undo the recovery
+        env.storage().instance().remove(&DataKey::Recovery);
+        env.storage().instance().remove(&DataKey::Rotation);
+        Self::reset_signers(&env, recovery.credential_id.clone(), recovery.public_key);
+        events::recovery_finished(&env, &recovery.credential_id);
+        events::owner_changed(&env, &recovery.credential_id);
+
+        Ok(())
+    }
+
+    /// Propose how long owner rotations and account changes wait before they
+    /// can be applied (requires the account's auth)
+    ///
+    /// The new delay takes effect with `apply_change` once the current delay
+    /// has passed.
+    ///
+    /// # Errors
+    /// * `InvalidRotationDelay` - If the delay is shorter than one hour
+    ///
+    /// # Returns
+    /// The ID of the pending change
+    pub fn set_rotation_delay(env: Env, delay: u64) -> Result<u32, Error> {
+        Self::get_signers(&env)?;
+        env.current_contract_address().require_auth();
+
+        Self::propose_change(&env, AccountChange::SetRotationDelay(delay))
+    }
+
+    /// Get the delay between proposing and applying an owner rotation (seconds)
+    pub fn get_rotation_delay(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::RotationDelay)
+            .unwrap_or(DEFAULT_ROTATION_DELAY)
+    }
+
+    /// Propose replacing every registered passkey with a single new one
+    /// (requires the account's auth)
+    ///
+    /// The rotation can be applied once the rotation delay has passed, and
+    /// cancelled by the current passkeys until then.
+    ///
+    /// # Errors
+    /// * `RotationAlreadyPending` - If a rotation is already in progress
+    /// * `InvalidPublicKey` - If the new key is not on the P-256 curve
+    pub fn propose_owner(
+        env: Env,
+        new_public_key: BytesN<64>,
+        new_credential_id: Bytes,
+    ) -> Result<(), Error> {
+        // This will call __check_auth to verify the current owner
+        Self::get_signers(&env)?;
+        env.current_contract_address().require_auth();
+
+        if env.storage().instance().has(&DataKey::Rotation) {
+            return Err(Error::RotationAlreadyPending);
+        }
+        let new_public_key = normalize_public_key(&env, &new_public_key.into())?;
+
+        let rotation = PendingOwner {
+            credential_id: new_credential_id,
+            public_key: new_public_key,
+            effective_at: env
+                .ledger()
+                .timestamp()
+                .saturating_add(Self::get_rotation_delay(env.clone())),
+        };
+        env.storage().instance().set(&DataKey::Rotation, &rotation);
+        events::rotation_proposed(&env, &rotation.credential_id, rotation.effective_at);
+
+        Ok(())
+    }
+
+    /// Get the owner rotation in progress (if any)
+    pub fn get_pending_rotation(env: Env) -> Option<PendingOwner> {
+        env.storage().instance().get(&DataKey::Rotation)
+    }
+
+    /// Cancel the owner rotation in progress (requires the account's auth)
+    ///
+    /// # Errors
+    /// * `RotationNotPending` - If there is no rotation in progress
+    pub fn cancel_rotation(env: Env) -> Result<(), Error> {
+        Self::get_signers(&env)?;
+        env.current_contract_address().require_auth();
+
+        let rotation = Self::get_pending_rotation(env.clone()).ok_or(Error::RotationNotPending)?;
+        env.storage().instance().remove(&DataKey::Rotation);
+        events::rotation_cancelled(&env, &rotation.credential_id);
+
+        Ok(())
+    }
+
+    /// Apply an owner rotation whose delay has passed, making the new passkey
+    /// the only signer and resetting the threshold to 1 (callable by anyone)
+    ///
+    /// # Errors
+    /// * `RotationNotPending` - If there is no rotation in progress
+    /// * `RotationTimelocked` - If the rotation delay has not passed yet
+    pub fn apply_rotation(env: Env) -> Result<(), Error> {
+        Self::get_signers(&env)?;
+
+        let rotation = Self::get_pending_rotation(env.clone()).ok_or(Error::RotationNotPending)?;
+        if env.ledger().timestamp() < rotation.effective_at {
+            return Err(Error::RotationTimelocked);
+        }
+
+        env.storage().instance().remove(&DataKey::Rotation);
+        Self::reset_signers(&env, rotation.credential_id.clone(), rotation.public_key);
+        events::rotation_applied(&env, &rotation.credential_id);
+        events::owner_changed(&env, &rotation.credential_id);
+
+        Ok(())
+    }
+
+    /// Get the account changes waiting for their timelock (change ID -> change)
+    pub fn list_pending_changes(env: Env) -> Map<u32, PendingChange> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Changes)
+            .unwrap_or(Map::new(&env))
+    }
+
+    /// Cancel a pending account change (requires the account's auth)
+    ///
+    /// # Errors
+    /// * `ChangeNotPending` - If there is no pending change with the given ID
+    pub fn cancel_change(env: Env, id: u32) -> Result<(), Error> {
+        Self::get_signers(&env)?;
+        env.current_contract_address().require_auth();
+
+        let mut changes = Self::list_pending_changes(env.clone());
+        if changes.remove(id).is_none() {
+            return Err(Error::ChangeNotPending);
+        }
+        env.storage().instance().set(&DataKey::Changes, &changes);
+        events::change_cancelled(&env, id);
+
+        Ok(())
+    }
+
+    /// Apply an account change whose timelock has expired (callable by anyone)
+    ///
+    /// The change is checked again against the current state, since other
+    /// changes may have been applied in the meantime.
+    ///
+    /// # Errors
+    /// * `ChangeNotPending` - If there is no pending change with the given ID
+    /// * `ChangeTimelocked` - If the rotation delay has not passed yet
+    /// * Any error of the entry point that proposed the change
+    pub fn apply_change(env: Env, id: u32) -> Result<(), Error> {
+        Self::get_signers(&env)?;
+
+        let mut changes = Self::list_pending_changes(env.clone());
+        let pending = changes.get(id).ok_or(Error::ChangeNotPending)?;
+        if env.ledger().timestamp() < pending.effective_at {
+            return Err(Error::ChangeTimelocked);
+        }
+
+        changes.remove(id);
+        env.storage().instance().set(&DataKey::Changes, &changes);
+        events::change_applied(&env, id);
+        Self::execute_change(&env, pending.change)
+    }
+}
+
+// Helper functions
+impl PasskeyAccount {
+    /// Get the registered passkeys, failing if the contract is not initialized
+    fn get_signers(env: &Env) -> Result<Map<Bytes, Signer>, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Signers)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Check that a session key is still valid and that every call being
+    /// authorized is within its scope
+    fn check_session_key(
+        env: &Env,
+        session_key: &SessionKey,
+        auth_context: &Vec<Context>,
+    ) -> Result<(), Error> {
+        if env.ledger().timestamp() >= session_key.expires_at {
+            return Err(Error::SessionKeyExpired);
+        }
+
+        for context in auth_context.iter() {
+            match context {
+                Context::Contract(call) => {
+                    if call.contract == env.current_contract_address()
+                        || !session_key.contracts.contains(call.contract)
+                        || !session_key.functions.contains(call.fn_name)
+                    {
+                        return Err(Error::SessionKeyNotAllowed);
+                    }
+                }
+                // Session keys never deploy contracts
+                Context::CreateContractHostFn(_) => return Err(Error::SessionKeyNotAllowed),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check every call being authorized against the call policy (if any)
+    fn check_call_policy(env: &Env, auth_context: &Vec<Context>) -> Result<(), Error> {
+        let Some(policy) = Self::get_call_policy(env.clone()) else {
+            return Ok(());
+        };
+
+        for context in auth_context.iter() {
+            let Context::Contract(call) = context else {
+                // Deploying contracts is never allow-listed
+                return Err(Error::CallNotAllowed);
+            };
+            if call.contract == env.current_contract_address() {
+                continue;
+            }
+            if policy.denied.contains(call.contract.clone()) {
+                return Err(Error::CallNotAllowed);
+            }
+            let functions = policy
+                .allowed
+                .get(call.contract)
+                .ok_or(Error::CallNotAllowed)?;
+            if !functions.is_empty() && !functions.contains(call.fn_name) {
+                return Err(Error::CallNotAllowed);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the spends of `token` still inside the weekly window
+    fn get_spends(env: &Env, token: &Address) -> Vec<Spend> {
+        let now = env.ledger().timestamp();
+        let key = DataKey::Spends(token.clone());
+        let spends: Vec<Spend> = match env.storage().persistent().get(&key) {
+            Some(spends) => {
+                Self::extend_persistent(env, &key);
+                spends
+            }
+            None => Vec::new(env),
+        };
+
+        let mut recent = Vec::new(env);
+        for spend in spends.iter() {
+            if now.saturating_sub(spend.timestamp) < WEEK {
+                recent.push_back(spend);
+            }
+        }
+        recent
+    }
+
+    /// Sum the spends made in the last day and in the last week
+    fn spent_in_windows(env: &Env, spends: &Vec<Spend>) -> (i128, i128) {
+        let now = env.ledger().timestamp();
+        let mut daily = 0i128;
+        let mut weekly = 0i128;
+        for spend in spends.iter() {
+            if now.saturating_sub(spend.timestamp) < DAY {
+                daily = daily.saturating_add(spend.amount);
+            }
+            weekly = weekly.saturating_add(spend.amount);
+        }
+        (daily, weekly)
+    }
+
+    /// Record the SEP-41 `transfer`/`approve` calls being authorized against
+    /// the spending limits of their tokens
+    ///
+    /// Returns whether any of them goes over a limit.
+    fn record_spending(env: &Env, auth_context: &Vec<Context>) -> Result<bool, Error> {
+        let mut over_limit = false;
+
+        for context in auth_context.iter() {
+            let Context::Contract(call) = context else {
+                continue;
+            };
+            if call.fn_name != symbol_short!("transfer") && call.fn_name != symbol_short!("approve")
+            {
+                continue;
+            }
+            let Some(limit) = Self::get_spending_limit(env.clone(), call.contract.clone()) else {
+                continue;
+            };
+
+            // Both `transfer(from, to, amount)` and
+            // `approve(from, spender, amount, expiration_ledger)` carry the amount third
+            let amount = call
+                .args
+                .get(2)
+                .and_then(|arg| i128::try_from_val(env, &arg).ok())
+                .ok_or(Error::SpendingLimitExceeded)?
+                .max(0);
+
+            let mut spends = Self::get_spends(env, &call.contract);
+            spends.push_back(Spend {
+                timestamp: env.ledger().timestamp(),
+                amount,
+            });
+            let (daily, weekly) = Self::spent_in_windows(env, &spends);
+            if daily > limit.daily || weekly > limit.weekly {
+                over_limit = true;
+            }
+
+            let key = DataKey::Spends(call.contract);
+            env.storage().persistent().set(&key, &spends);
+            Self::extend_persistent(env, &key);
+        }
+
+        Ok(over_limit)
+    }
+
+    /// Check that every call being authorized is one of the account functions
+    /// the signer is restricted to (if any)
+    fn check_signer_policy(
+        env: &Env,
+        signer: &Signer,
+        auth_context: &Vec<Context>,
+    ) -> Result<(), Error> {
+        if signer.functions.is_empty() {
+            return Ok(());
+        }
+
+        for context in auth_context.iter() {
+            let Context::Contract(call) = context else {
+                return Err(Error::SignerNotAllowed);
+            };
+            if call.contract != env.current_contract_address()
+                || !signer.functions.contains(call.fn_name)
+            {
+                return Err(Error::SignerNotAllowed);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Make `public_key` the only signer, with weight 1 and threshold 1
+    ///
+    /// Session keys registered with the previous signers are left behind in
+    /// the old epoch and can no longer authorize, and their pending account
+    /// changes are dropped.
+    fn reset_signers(env: &Env, credential_id: Bytes, public_key: BytesN<64>) {
+        let mut signers = Map::new(env);
+        signers.set(
+            credential_id,
+            Signer {
+                key: SignerKey::Secp256r1(public_key),
+                weight: 1,
+                functions: Vec::new(env),
+            },
+        );
+        env.storage().instance().set(&DataKey::Signers, &signers);
+        env.storage().instance().set(&DataKey::Threshold, &1u32);
+
+        let epoch = Self::signer_epoch(env).saturating_add(1);
+        env.storage().instance().set(&DataKey::SignerEpoch, &epoch);
+        // Changes proposed by the previous signers must not outlive them
+        env.storage().instance().remove(&DataKey::Changes);
+    }
+
+    /// Extend the TTL of a persistent entry to the maximum
+    fn extend_persistent(env: &Env, key: &DataKey) {
+        let max_ttl = env.storage().max_ttl();
+        env.storage().persistent().extend_ttl(key, max_ttl, max_ttl);
+    }
+
+    /// Extend the TTL of the instance entries (signers, guardians, recovery)
+    /// to the maximum
+    fn extend_instance(env: &Env) {
+        let max_ttl = env.storage().max_ttl();
+        env.storage().instance().extend_ttl(max_ttl, max_ttl);
+    }
+
+    /// Check that `change` can be applied to the current state
+    fn check_change(env: &Env, change: &AccountChange) -> Result<(), Error> {
+        let signers = Self::get_signers(env)?;
+
+        match change {
+            AccountChange::AddSigner(credential_id, signer) => {
+                if signers.contains_key(credential_id.clone()) {
+                    return Err(Error::SignerAlreadyExists);
+                }
+                // One key under several credential IDs would count its weight
+                // more than once towards the threshold.
+                if signers.values().iter().any(|other| other.key == signer.key) {
+                    return Err(Error::KeyAlreadyRegistered);
+                }
+                if signer.weight == 0 {
+                    return Err(Error::InvalidWeight);
+                }
+                validate_signer_key(&signer.key)
+            }
+            AccountChange::RemoveSigner(credential_id) => {
+                let mut signers = signers;
+                if signers.remove(credential_id.clone()).is_none() {
+                    return Err(Error::SignerNotFound);
+                }
+                if signers.is_empty() {
+                    return Err(Error::LastSigner);
+                }
+                if total_weight(&signers) < Self::get_threshold(env.clone())? {
+                    return Err(Error::InvalidThreshold);
+                }
+                Ok(())
+            }
+            AccountChange::SetThreshold(threshold) => {
+                if *threshold == 0 || *threshold > total_weight(&signers) {
+                    return Err(Error::InvalidThreshold);
+                }
+                Ok(())
+            }
+            AccountChange::SetGuardians(config) => {
+                if config.quorum == 0 || config.quorum > config.guardians.len() {
+                    return Err(Error::InvalidGuardians);
+                }
+                let mut seen: Map<Address, ()> = Map::new(env);
+                for guardian in config.guardians.iter() {
+                    if guardian == env.current_contract_address()
+                        || seen.contains_key(guardian.clone())
+                    {
+                        return Err(Error::InvalidGuardians);
+                    }
+                    seen.set(guardian, ());
+                }
+                Ok(())
+            }
+            AccountChange::SetRotationDelay(delay) => {
+                if *delay < MIN_ROTATION_DELAY {
+                    return Err(Error::InvalidRotationDelay);
+                }
+                Ok(())
+            }
+            AccountChange::Upgrade(_) => Ok(()),
+            AccountChange::SetSpendingLimit(_, limit) => {
+                if limit.daily <= 0 || limit.weekly <= 0 || limit.daily > limit.weekly {
+                    return Err(Error::InvalidSpendingLimit);
+                }
+                Ok(())
+            }
+            AccountChange::RemoveSpendingLimit(_)
+            | AccountChange::SetCallPolicy(_)
+            | AccountChange::RemoveCallPolicy => Ok(()),
+        }
+    }
+
+    /// Apply a change that tightens the account's policy at once, or queue
+    /// one that loosens it until the rotation delay has passed
+    fn execute_or_propose(
+        env: &Env,
+        change: AccountChange,
+        tightens: bool,
+    ) -> Result<Option<u32>, Error> {
+        if tightens {
+            Self::execute_change(env, change)?;
+            return Ok(None);
+        }
+        Self::propose_change(env, change).map(Some)
+    }
+
+    /// Queue `change` until the current rotation delay has passed
+    fn propose_change(env: &Env, change: AccountChange) -> Result<u32, Error> {
+        Self::check_change(env, &change)?;
+
+        let id: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::NextChangeId)
+            .unwrap_or(0);
+        let effective_at = env
+            .ledger()
+            .timestamp()
+            .saturating_add(Self::get_rotation_delay(env.clone()));
+
+        let mut changes = Self::list_pending_changes(env.clone());
+        changes.set(
+            id,
+            PendingChange {
+                change,
+                effective_at,
+            },
+        );
+        env.storage().instance().set(&DataKey::Changes, &changes);
+        env.storage()
+            .instance()
+            .set(&DataKey::NextChangeId, &(id + 1));
+        events::change_proposed(env, id, effective_at);
+
+        Ok(id)
+    }
+
+    /// Apply `change` after checking it against the current state
+    fn execute_change(env: &Env, change: AccountChange) -> Result<(), Error> {
+        Self::check_change(env, &change)?;
+        let storage = env.storage().instance();
+
+        match change {
+            AccountChange::AddSigner(credential_id, signer) => {
+                let mut signers = Self::get_signers(env)?;
+                let weight = signer.weight;
+                signers.set(credential_id.clone(), signer);
+                storage.set(&DataKey::Signers, &signers);
+                events::signer_added(env, &credential_id, weight);
+            }
+            AccountChange::RemoveSigner(credential_id) => {
+                let mut signers = Self::get_signers(env)?;
+                signers.remove(credential_id.clone());
+                storage.set(&DataKey::Signers, &signers);
+                events::signer_removed(env, &credential_id);
+            }
+            AccountChange::SetThreshold(threshold) => {
+                storage.set(&DataKey::Threshold, &threshold);
+                events::threshold_changed(env, threshold);
+            }
+            AccountChange::SetGuardians(config) => {
+                storage.set(&DataKey::Guardians, &config);
+                Self::extend_instance(env);
+                events::guardians_changed(env, config.guardians.len(), config.quorum);
+            }
+            AccountChange::SetRotationDelay(delay) => {
+                storage.set(&DataKey::RotationDelay, &delay);
+                events::rotation_delay_changed(env, delay);
+            }
+            AccountChange::Upgrade(new_wasm_hash) => {
+                env.deployer()
+                    .update_current_contract_wasm(new_wasm_hash.clone());
+                events::account_upgraded(env, &new_wasm_hash);
+            }
+            AccountChange::SetSpendingLimit(token, limit) => {
+                let key = DataKey::SpendingLimit(token.clone());
+                env.storage().persistent().set(&key, &limit);
+                Self::extend_persistent(env, &key);
+                events::spending_limit_set(env, &token, limit.daily, limit.weekly);
+            }
+            AccountChange::RemoveSpendingLimit(token) => {
+                env.storage()
+                    .persistent()
+                    .remove(&DataKey::SpendingLimit(token.clone()));
+                env.storage()
+                    .persistent()
+                    .remove(&DataKey::Spends(token.clone()));
+                events::spending_limit_removed(env, &token);
+            }
+            AccountChange::SetCallPolicy(policy) => {
+                storage.set(&DataKey::CallPolicy, &policy);
+                events::call_policy_set(env, policy.allowed.len(), policy.denied.len());
+            }
+            AccountChange::RemoveCallPolicy => {
+                storage.remove(&DataKey::CallPolicy);
+                events::call_policy_removed(env);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the current signer epoch
+    fn signer_epoch(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::SignerEpoch)
+            .unwrap_or(0)
+    }
+
+    /// Storage key of the session key registered under `id` in the current signer epoch
+    fn session_key_entry(env: &Env, id: Bytes) -> DataKey {
+        DataKey::SessionKey(Self::signer_epoch(env), id)
+    }
+}
+
+/// Implementation of CustomAccountInterface for Soroban authentication
+#[contractimpl]
+impl CustomAccountInterface for PasskeyAccount {
+    type Signature = Vec<SignerSignature>;
+    type Error = Error;
+
+    /// The authentication entry point
+    ///
+    /// This function is called by the Soroban host when `require_auth()` is invoked
+    /// on the contract's address. It verifies the secp256r1 signatures from the
+    /// account's passkeys (or ed25519 signatures from backup keys), dispatching on
+    /// the signature type, and checks that their weights reach the threshold.
+    /// Signers restricted to some account functions can only authorize those.
+    /// A valid session key signature authorizes on its own, as long as every
+    /// call in the authorization context is within the key's scope.
+    ///
+    /// When a call policy is set, every call must be allow-listed by it.
+    /// Token `transfer`/`approve` calls are counted against the configured
+    /// spending limits. Going over a limit needs passkey signatures weighing more
+    /// than the threshold, so a session key alone can never do it.
+    ///
+    /// # Arguments
+    /// * `signature_payload` - The payload that was signed (32-byte hash)
+    /// * `signatures` - Credential ID of each signer (or session key ID) and its
+    ///   secp256r1 or ed25519 signature
+    /// * `auth_context` - The calls being authorized, checked against session key scopes
+    ///
+    /// # Returns
+    /// * `Ok(())` if the signatures are valid and reach the threshold, after
+    ///   publishing an `(auth, used)` event for each signer
+    /// * `Err(Error::NotInitialized)` if the account has no signers
+    /// * `Err(Error::DuplicateSigner)` if the same credential ID signed more than once
+    /// * `Err(Error::SignerNotFound)` if a credential ID is neither a passkey nor a
+    ///   session key registered since the last recovery or rotation
+    /// * `Err(Error::SessionKeyExpired)` if a session key is past its expiry
+    /// * `Err(Error::SessionKeyNotAllowed)` if a call is outside a session key's scope
+    /// * `Err(Error::Secp256r1SignatureParse)` if a secp256r1 signature is neither DER
+    ///   nor 64-byte r || s, or r or s is zero or not below the curve order
+    /// * `Err(Error::InvalidSignature)` if a signature's scheme does not match the
+    ///   signer's key
+    /// * `Err(Error::SignerNotAllowed)` if a call is outside a signer's allowed functions
+    /// * `Err(Error::ThresholdNotMet)` if the signer weights do not reach the threshold
+    /// * `Err(Error::CallNotAllowed)` if a call is not allowed by the call policy
+    /// * `Err(Error::SpendingLimitExceeded)` if a spending limit is exceeded without an
+    ///   additional signer, or a limited token call has no `i128` amount as its
+    ///   third argument
+    ///
+    /// # Panics
+    /// * If a secp256r1 or ed25519 signature does not verify against the signer's
+    ///   key, since the host traps instead of returning an error. Checking it in
+    ///   the contract first would return an error but adds a full P-256
+    ///   implementation, about 10 KB of WASM, for the same outcome: the
+    ///   authorization fails either way
+    #[allow(non_snake_case)]
+    fn __check_auth(
+        env: Env,
+        signature_payload: Hash<32>,
+        signatures: Self::Signature,
+        auth_context: Vec<Context>,
+    ) -> Result<(), Self::Error> {
+        let signers = PasskeyAccount::get_signers(&env)?;
+        let threshold = PasskeyAccount::get_threshold(env.clone())?;
+
+        let mut signed: Map<Bytes, ()> = Map::new(&env);
+        let mut weight = 0u32;
+        let mut session_authorized = false;
+        for signature in signatures.iter() {
+            if signed.contains_key(signature.credential_id.clone()) {
+                return Err(Error::DuplicateSigner);
+            }
+            signed.set(signature.credential_id.clone(), ());
+
+            // Select the public key of the signer that signed
+            if let Some(signer) = signers.get(signature.credential_id.clone()) {
+                verify_signature(&env, &signer.key, &signature_payload, &signature.signature)?;
+                PasskeyAccount::check_signer_policy(&env, &signer, &auth_context)?;
+                weight = weight.saturating_add(signer.weight);
+                continue;
+            }
+
+            // Otherwise it must be a session key acting within its scope
+            let session_key = PasskeyAccount::get_session_key(env.clone(), signature.credential_id)
+                .ok_or(Error::SignerNotFound)?;
+            PasskeyAccount::check_session_key(&env, &session_key, &auth_context)?;
+            verify_signature(
+                &env,
+                &session_key.key,
+                &signature_payload,
+                &signature.signature,
+            )?;
+            session_authorized = true;
+        }
+
+        if weight < threshold && !session_authorized {
+            return Err(Error::ThresholdNotMet);
+        }
+
+        PasskeyAccount::check_call_policy(&env, &auth_context)?;
+
+        if PasskeyAccount::record_spending(&env, &auth_context)? && weight <= threshold {
+            return Err(Error::SpendingLimitExceeded);
+        }
+
+        for credential_id in signed.keys() {
+            events::auth_used(&env, &credential_id);
+        }
+
+        Ok(())
+    }
+}
+
+/// Validate a secp256r1 public key in any accepted encoding and convert it
+/// to the stored X || Y form
+fn normalize_public_key(env: &Env, public_key: &Bytes) -> Result<BytesN<64>, Error> {
+    let sec1 = public_key::to_uncompressed(public_key).ok_or(Error::InvalidPublicKey)?;
+    let mut coordinates = [0u8; 64];
+    coordinates.copy_from_slice(&sec1[1..]);
+    Ok(BytesN::from_array(env, &coordinates))
+}
+
+/// Check that a secp256r1 signer key is a point on the P-256 curve
+/// (ed25519 keys are checked by the host when verifying)
+fn validate_signer_key(key: &SignerKey) -> Result<(), Error> {
+    if let SignerKey::Secp256r1(public_key) = key {
+        public_key::to_uncompressed(&public_key.clone().into()).ok_or(Error::InvalidPublicKey)?;
+    }
+    Ok(())
+}
+
+/// Sum of the weights of all signers
+fn total_weight(signers: &Map<Bytes, Signer>) -> u32 {
+    signers
+        .values()
+        .iter()
+        .fold(0u32, |total, signer| total.saturating_add(signer.weight))
+}
+
+/// Whether `policy` rejects every call that `current` rejects: each allowed
+/// contract and function is also allowed by `current`, and every contract
+/// denied by `current` stays denied
+fn call_policy_tightens(current: &CallPolicy, policy: &CallPolicy) -> bool {
+    let allowed = policy.allowed.iter().all(|(contract, functions)| {
+        match current.allowed.get(contract) {
+            // An empty list allows every function of the contract
+            Some(current_functions) => {
+                current_functions.is_empty()
+                    || (!functions.is_empty()
+                        && functions.iter().all(|f| current_functions.contains(f)))
+            }
+            None => false,
+        }
+    });
+    allowed
+        && current
+            .denied
+            .iter()
+            .all(|contract| policy.denied.contains(contract))
+}
+
+/// Verify a signature over the payload with the key's scheme
+///
+/// A signature of a different scheme than the key is rejected with
+/// `InvalidSignature`. A secp256r1 or ed25519 signature that does not match
+/// the key traps inside the host, which aborts the authorization.
+fn verify_signature(
+    env: &Env,
+    key: &SignerKey,
+    signature_payload: &Hash<32>,
+    signature: &Signature,
+) -> Result<(), Error> {
+    match (key, signature) {
+        (SignerKey::Secp256r1(public_key), Signature::Secp256r1(signature)) => {
+            verify_secp256r1(env, public_key, signature_payload, signature)
+        }
+        (SignerKey::Ed25519(public_key), Signature::Ed25519(signature)) => {
+            env.crypto().ed25519_verify(
+                public_key,
+                &signature_payload.to_bytes().into(),
+                signature,
+            );
+            Ok(())
+        }
+        _ => Err(Error::InvalidSignature),
+    }
+}
+
+/// Verify a secp256r1 signature over the payload
+///
+/// The signature is decoded from DER or r || s, and a high s is replaced by
+/// its low form, which is the only one the host accepts. Malformed signatures
+/// or r and s outside the curve order are rejected with
+/// `Secp256r1SignatureParse`. A signature that does not match the key traps
+/// inside the host, which aborts the authorization.
+fn verify_secp256r1(
+    env: &Env,
+    public_key: &BytesN<64>,
+    signature_payload: &Hash<32>,
+    signature: &Bytes,
+) -> Result<(), Error> {
+    let signature = ecdsa::parse(signature).ok_or(Error::Secp256r1SignatureParse)?;
+    env.crypto().secp256r1_verify(
+        &sec1_public_key(env, public_key),
+        signature_payload,
+        &BytesN::from_array(env, &signature),
+    );
+
+    Ok(())
+}
+
+/// Convert the stored X || Y coordinates into the SEC-1 uncompressed
+/// encoding (0x04 || X || Y) expected by `secp256r1_verify`
+fn sec1_public_key(env: &Env, public_key: &BytesN<64>) -> BytesN<65> {
+    let mut sec1 = [0u8; 65];
+    sec1[0] = 0x04;
+    sec1[1..].copy_from_slice(&public_key.to_array());
+    BytesN::from_array(env, &sec1)
+}
+
+#[cfg(test)]
+mod test;
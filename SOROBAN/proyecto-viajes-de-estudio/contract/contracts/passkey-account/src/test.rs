@@ -0,0 +1,2129 @@
+#![cfg(test)]
+extern crate std;
+
+use super::*;
+use ed25519_dalek::Signer as _;
+use p256::ecdsa::{signature::hazmat::PrehashSigner, Signature as P256Signature, SigningKey};
+use soroban_sdk::{
+    auth::ContractContext,
+    symbol_short,
+    testutils::{
+        storage::{Instance as _, Persistent as _},
+        Address as _, Events, Ledger,
+    },
+    vec, Address, IntoVal, Symbol, Val,
+};
+
+// Placeholder tests for the PasskeyAccount contract
+// These tests verify the basic structure of the contract
+
+#[test]
+fn test_passkey_account_contract_exists() {
+    // Test that the contract can be defined
+    let _contract = PasskeyAccount;
+    // If this compiles without errors, the contract structure is valid
+}
+
+#[test]
+fn test_data_key_enum_exists() {
+    // Verify that DataKey enum can be instantiated
+    let _owner_key = DataKey::Owner;
+    let _cred_id_key = DataKey::CredentialId;
+}
+
+#[test]
+fn test_signer_data_keys_exist() {
+    // Keys of the multi-signer layout (schema version 2)
+    let _signers_key = DataKey::Signers;
+    let _threshold_key = DataKey::Threshold;
+}
+
+#[test]
+fn test_error_codes_valid() {
+    // Verify that error codes are defined correctly
+    assert_eq!(Error::AlreadyInitialized as u32, 1);
+    assert_eq!(Error::NotInitialized as u32, 2);
+    assert_eq!(Error::InvalidPublicKey as u32, 3);
+    assert_eq!(Error::InvalidSignature as u32, 4);
+}
+
+#[test]
+fn test_error_enum_values() {
+    // Test error enum discriminants
+    let err1 = Error::AlreadyInitialized;
+    let err2 = Error::NotInitialized;
+    let err3 = Error::InvalidPublicKey;
+    let err4 = Error::InvalidSignature;
+
+    assert_ne!(err1, err2);
+    assert_ne!(err2, err3);
+    assert_ne!(err3, err4);
+}
+
+// Signature verification tests using a software P-256 key
+
+fn signing_key(seed: u8) -> SigningKey {
+    SigningKey::from_slice(&[seed; 32]).unwrap()
+}
+
+fn public_key(env: &Env, key: &SigningKey) -> BytesN<64> {
+    let point = key.verifying_key().to_encoded_point(false);
+    BytesN::from_array(env, point.as_bytes()[1..].try_into().unwrap())
+}
+
+/// The passkey derived from `seed`, as registered with `add_signer`
+fn passkey(env: &Env, seed: u8) -> SignerKey {
+    SignerKey::Secp256r1(public_key(env, &signing_key(seed)))
+}
+
+fn credential_id(env: &Env, seed: u8) -> Bytes {
+    Bytes::from_array(env, &[seed; 16])
+}
+
+/// 32 bytes derived from `seed`, used instead of random values so that
+/// the test snapshots are the same on every run
+fn fixed_bytes(env: &Env, seed: u8) -> BytesN<32> {
+    env.crypto()
+        .sha256(&Bytes::from_array(env, &[seed]))
+        .to_bytes()
+}
+
+fn raw_signature(env: &Env, key: &SigningKey, payload: &BytesN<32>) -> Bytes {
+    Bytes::from_slice(env, &p256_signature(key, payload).to_bytes())
+}
+
+fn p256_signature(key: &SigningKey, payload: &BytesN<32>) -> P256Signature {
+    let signature: P256Signature = key.sign_prehash(&payload.to_array()).unwrap();
+    signature.normalize_s().unwrap_or(signature)
+}
+
+/// Sign `payload` with the passkey derived from `seed`
+fn sign(env: &Env, seed: u8, payload: &BytesN<32>) -> SignerSignature {
+    SignerSignature {
+        credential_id: credential_id(env, seed),
+        signature: Signature::Secp256r1(raw_signature(env, &signing_key(seed), payload)),
+    }
+}
+
+/// Sign `payload` with each of the passkeys derived from `seeds`
+fn signed_by(env: &Env, seeds: &[u8], payload: &BytesN<32>) -> Vec<SignerSignature> {
+    let mut signatures = Vec::new(env);
+    for seed in seeds {
+        signatures.push_back(sign(env, *seed, payload));
+    }
+    signatures
+}
+
+/// Register an account whose first passkey is derived from `seed`
+fn setup(env: &Env, seed: u8) -> Address {
+    let contract_id = env.register_contract(None, PasskeyAccount);
+    let client = PasskeyAccountClient::new(env, &contract_id);
+    client.init(
+        &public_key(env, &signing_key(seed)).into(),
+        &credential_id(env, seed),
+    );
+    contract_id
+}
+
+/// Apply a pending account change once the rotation delay has passed
+fn apply_after_delay(env: &Env, contract_id: &Address, id: u32) {
+    let client = PasskeyAccountClient::new(env, contract_id);
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp += client.get_rotation_delay());
+    client.apply_change(&id);
+}
+
+fn check_auth(
+    env: &Env,
+    contract_id: &Address,
+    payload: &BytesN<32>,
+    signatures: Vec<SignerSignature>,
+) -> Result<(), Result<Error, soroban_sdk::InvokeError>> {
+    check_auth_for(env, contract_id, payload, signatures, vec![env])
+}
+
+fn check_auth_for(
+    env: &Env,
+    contract_id: &Address,
+    payload: &BytesN<32>,
+    signatures: Vec<SignerSignature>,
+    auth_context: Vec<Context>,
+) -> Result<(), Result<Error, soroban_sdk::InvokeError>> {
+    env.try_invoke_contract_check_auth::<Error>(
+        contract_id,
+        payload,
+        signatures.into_val(env),
+        &auth_context,
+    )
+}
+
+#[test]
+fn test_check_auth_valid_signature() {
+    let env = Env::default();
+    let contract_id = setup(&env, 1);
+
+    let payload = fixed_bytes(&env, 1);
+    assert_eq!(
+        check_auth(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[1], &payload)
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_check_auth_rejects_signature_over_other_payload() {
+    let env = Env::default();
+    let contract_id = setup(&env, 1);
+
+    let payload = fixed_bytes(&env, 1);
+    let signatures = signed_by(&env, &[1], &fixed_bytes(&env, 2));
+    // The host traps on a signature that does not match
+    assert!(matches!(
+        check_auth(&env, &contract_id, &payload, signatures),
+        Err(Err(_))
+    ));
+}
+
+#[test]
+fn test_check_auth_rejects_signature_from_other_key() {
+    let env = Env::default();
+    let contract_id = setup(&env, 1);
+
+    // Claims the registered credential ID but is signed with another key
+    let payload = fixed_bytes(&env, 1);
+    let signature = SignerSignature {
+        credential_id: credential_id(&env, 1),
+        signature: Signature::Secp256r1(raw_signature(&env, &signing_key(2), &payload)),
+    };
+    assert!(matches!(
+        check_auth(&env, &contract_id, &payload, vec![&env, signature]),
+        Err(Err(_))
+    ));
+}
+
+#[test]
+fn test_check_auth_rejects_malformed_signature() {
+    let env = Env::default();
+    let contract_id = setup(&env, 1);
+    let payload: BytesN<32> = fixed_bytes(&env, 1);
+
+    let der = p256_signature(&signing_key(1), &payload).to_der();
+    let mut truncated_der = der.as_bytes().to_vec();
+    truncated_der.pop();
+    let mut negative_r = der.as_bytes().to_vec();
+    negative_r[4] |= 0x80;
+
+    for malformed in [
+        // Zero scalars
+        std::vec![0u8; 64],
+        // Neither DER nor r || s
+        std::vec![1u8; 65],
+        truncated_der,
+        negative_r,
+    ] {
+        let mut signature = sign(&env, 1, &payload);
+        signature.signature = Signature::Secp256r1(Bytes::from_slice(&env, &malformed));
+        assert_eq!(
+            check_auth(&env, &contract_id, &payload, vec![&env, signature]),
+            Err(Ok(Error::Secp256r1SignatureParse))
+        );
+    }
+}
+
+#[test]
+fn test_check_auth_accepts_der_and_high_s_signatures() {
+    let env = Env::default();
+    let contract_id = setup(&env, 1);
+    let payload: BytesN<32> = fixed_bytes(&env, 1);
+
+    let low_s = p256_signature(&signing_key(1), &payload);
+    let high_s = P256Signature::from_scalars(low_s.r(), -*low_s.s()).unwrap();
+
+    // High-S signatures are normalized to their low-S form before verifying
+    for encoded in [
+        low_s.to_der().as_bytes().to_vec(),
+        high_s.to_der().as_bytes().to_vec(),
+        high_s.to_bytes().to_vec(),
+    ] {
+        let mut signature = sign(&env, 1, &payload);
+        signature.signature = Signature::Secp256r1(Bytes::from_slice(&env, &encoded));
+        assert_eq!(
+            check_auth(&env, &contract_id, &payload, vec![&env, signature]),
+            Ok(())
+        );
+    }
+}
+
+#[test]
+fn test_check_auth_not_initialized() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PasskeyAccount);
+    let payload = fixed_bytes(&env, 1);
+
+    assert_eq!(
+        check_auth(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[1], &payload)
+        ),
+        Err(Ok(Error::NotInitialized))
+    );
+}
+
+// Multiple passkeys per account
+
+#[test]
+fn test_add_signer() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = setup(&env, 1);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let payload = fixed_bytes(&env, 1);
+
+    // A passkey that is not registered yet cannot sign
+    assert_eq!(
+        check_auth(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[2], &payload)
+        ),
+        Err(Ok(Error::SignerNotFound))
+    );
+
+    apply_after_delay(
+        &env,
+        &contract_id,
+        client.add_signer(&credential_id(&env, 2), &passkey(&env, 2), &1, &vec![&env]),
+    );
+    assert_eq!(client.list_signers().len(), 2);
+
+    // Both the phone and the laptop passkeys can now authorize
+    assert_eq!(
+        check_auth(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[1], &payload)
+        ),
+        Ok(())
+    );
+    assert_eq!(
+        check_auth(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[2], &payload)
+        ),
+        Ok(())
+    );
+
+    assert_eq!(
+        client.try_add_signer(&credential_id(&env, 2), &passkey(&env, 3), &1, &vec![&env]),
+        Err(Ok(Error::SignerAlreadyExists))
+    );
+}
+
+#[test]
+fn test_add_signer_rejects_registered_key() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = setup(&env, 1);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+
+    // The owner's passkey under a second credential ID would count twice
+    assert_eq!(
+        client.try_add_signer(&credential_id(&env, 9), &passkey(&env, 1), &1, &vec![&env]),
+        Err(Ok(Error::KeyAlreadyRegistered))
+    );
+
+    let backup_id = add_backup_key(&env, &contract_id, 7);
+    let backup_key = SignerKey::Ed25519(BytesN::from_array(
+        &env,
+        &ed25519_key(7).verifying_key().to_bytes(),
+    ));
+    assert_eq!(
+        client.try_add_signer(&credential_id(&env, 8), &backup_key, &1, &vec![&env]),
+        Err(Ok(Error::KeyAlreadyRegistered))
+    );
+
+    let signers = client.list_signers();
+    assert_eq!(signers.len(), 2);
+    assert!(signers.contains_key(backup_id));
+    assert!(!signers.contains_key(credential_id(&env, 8)));
+}
+
+#[test]
+fn test_remove_signer() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = setup(&env, 1);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let payload = fixed_bytes(&env, 1);
+
+    apply_after_delay(
+        &env,
+        &contract_id,
+        client.add_signer(&credential_id(&env, 2), &passkey(&env, 2), &1, &vec![&env]),
+    );
+    apply_after_delay(
+        &env,
+        &contract_id,
+        client.remove_signer(&credential_id(&env, 1)),
+    );
+
+    let signers = client.list_signers();
+    assert_eq!(signers.len(), 1);
+    assert_eq!(
+        signers.get(credential_id(&env, 2)).map(|signer| signer.key),
+        Some(passkey(&env, 2))
+    );
+
+    // The lost phone can no longer sign
+    assert_eq!(
+        check_auth(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[1], &payload)
+        ),
+        Err(Ok(Error::SignerNotFound))
+    );
+    assert_eq!(
+        client.try_remove_signer(&credential_id(&env, 1)),
+        Err(Ok(Error::SignerNotFound))
+    );
+    assert_eq!(
+        client.try_remove_signer(&credential_id(&env, 2)),
+        Err(Ok(Error::LastSigner))
+    );
+}
+
+#[test]
+fn test_signer_management_requires_account_auth() {
+    let env = Env::default();
+    let contract_id = setup(&env, 1);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+
+    assert!(client
+        .try_add_signer(&credential_id(&env, 2), &passkey(&env, 2), &1, &vec![&env])
+        .is_err());
+    assert_eq!(client.list_signers().len(), 1);
+
+    // Set up a second signer, a session key, a pending account change and a
+    // pending recovery and rotation with mocked auths, then call every admin
+    // entry point without them; each call must fail and leave the state
+    // untouched.
+    env.mock_all_auths();
+    apply_after_delay(
+        &env,
+        &contract_id,
+        client.add_signer(&credential_id(&env, 2), &passkey(&env, 2), &1, &vec![&env]),
+    );
+    let session_id = credential_id(&env, 3);
+    client.add_session_key(
+        &session_id,
+        &session_key(&env, passkey(&env, 3), &Address::generate(&env)),
+    );
+    let guardian = Address::generate(&env);
+    apply_after_delay(
+        &env,
+        &contract_id,
+        client.set_guardians(&GuardianConfig {
+            guardians: vec![&env, guardian.clone()],
+            quorum: 1,
+            delay: DAY,
+        }),
+    );
+    client.initiate_recovery(
+        &vec![&env, guardian],
+        &public_key(&env, &signing_key(4)),
+        &credential_id(&env, 4),
+    );
+    client.propose_owner(&public_key(&env, &signing_key(5)), &credential_id(&env, 5));
+    let change = client.set_threshold(&2);
+    env.set_auths(&[]);
+
+    let token = Address::generate(&env);
+    assert!(client.try_remove_signer(&credential_id(&env, 2)).is_err());
+    assert!(client.try_set_threshold(&2).is_err());
+    assert!(client
+        .try_add_session_key(
+            &credential_id(&env, 6),
+            &session_key(&env, passkey(&env, 6), &token)
+        )
+        .is_err());
+    assert!(client.try_revoke_session_key(&session_id).is_err());
+    assert!(client
+        .try_set_spending_limit(
+            &token,
+            &SpendingLimit {
+                daily: 1,
+                weekly: 1
+            }
+        )
+        .is_err());
+    assert!(client.try_remove_spending_limit(&token).is_err());
+    assert!(client
+        .try_set_call_policy(&CallPolicy {
+            allowed: Map::new(&env),
+            denied: vec![&env],
+        })
+        .is_err());
+    assert!(client.try_remove_call_policy().is_err());
+    assert!(client
+        .try_set_guardians(&GuardianConfig {
+            guardians: vec![&env, Address::generate(&env)],
+            quorum: 1,
+            delay: 0,
+        })
+        .is_err());
+    assert!(client.try_cancel_recovery().is_err());
+    assert!(client.try_set_rotation_delay(&(2 * DAY)).is_err());
+    assert!(client.try_cancel_rotation().is_err());
+    assert!(client.try_upgrade(&fixed_bytes(&env, 7)).is_err());
+    assert!(client.try_cancel_change(&change).is_err());
+
+    assert_eq!(client.list_signers().len(), 2);
+    assert_eq!(client.get_threshold(), 1);
+    assert!(client.get_session_key(&session_id).is_some());
+    assert_eq!(client.get_session_key(&credential_id(&env, 6)), None);
+    assert_eq!(client.get_spending_limit(&token), None);
+    assert_eq!(client.get_call_policy(), None);
+    assert_eq!(client.get_guardians().unwrap().delay, DAY);
+    assert!(client.get_recovery().is_some());
+    assert_eq!(client.get_rotation_delay(), DEFAULT_ROTATION_DELAY);
+    assert!(client.get_pending_rotation().is_some());
+    let changes = client.list_pending_changes();
+    assert_eq!(changes.len(), 1);
+    assert!(changes.contains_key(change));
+}
+
+// Weighted threshold policy
+
+/// An account with three staff passkeys of weight 1 that requires two of them
+fn setup_two_of_three(env: &Env) -> Address {
+    env.mock_all_auths();
+    let contract_id = setup(env, 1);
+    let client = PasskeyAccountClient::new(env, &contract_id);
+    apply_after_delay(
+        env,
+        &contract_id,
+        client.add_signer(&credential_id(env, 2), &passkey(env, 2), &1, &vec![env]),
+    );
+    apply_after_delay(
+        env,
+        &contract_id,
+        client.add_signer(&credential_id(env, 3), &passkey(env, 3), &1, &vec![env]),
+    );
+    apply_after_delay(env, &contract_id, client.set_threshold(&2));
+    contract_id
+}
+
+#[test]
+fn test_threshold_two_of_three() {
+    let env = Env::default();
+    let contract_id = setup_two_of_three(&env);
+    let payload = fixed_bytes(&env, 1);
+
+    assert_eq!(
+        check_auth(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[1], &payload)
+        ),
+        Err(Ok(Error::ThresholdNotMet))
+    );
+    assert_eq!(
+        check_auth(&env, &contract_id, &payload, Vec::new(&env)),
+        Err(Ok(Error::ThresholdNotMet))
+    );
+    assert_eq!(
+        check_auth(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[1, 3], &payload)
+        ),
+        Ok(())
+    );
+    assert_eq!(
+        check_auth(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[3, 2, 1], &payload)
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_threshold_rejects_duplicate_signer() {
+    let env = Env::default();
+    let contract_id = setup_two_of_three(&env);
+    let payload = fixed_bytes(&env, 1);
+
+    assert_eq!(
+        check_auth(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[2, 2], &payload)
+        ),
+        Err(Ok(Error::DuplicateSigner))
+    );
+}
+
+#[test]
+fn test_threshold_with_weights() {
+    let env = Env::default();
+    let contract_id = setup_two_of_three(&env);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let payload = fixed_bytes(&env, 1);
+
+    // The school director alone carries enough weight
+    apply_after_delay(
+        &env,
+        &contract_id,
+        client.add_signer(&credential_id(&env, 4), &passkey(&env, 4), &2, &vec![&env]),
+    );
+    assert_eq!(
+        check_auth(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[4], &payload)
+        ),
+        Ok(())
+    );
+    assert_eq!(
+        client.try_add_signer(&credential_id(&env, 5), &passkey(&env, 5), &0, &vec![&env]),
+        Err(Ok(Error::InvalidWeight))
+    );
+}
+
+#[test]
+fn test_threshold_must_stay_reachable() {
+    let env = Env::default();
+    let contract_id = setup_two_of_three(&env);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+
+    assert_eq!(
+        client.try_set_threshold(&0),
+        Err(Ok(Error::InvalidThreshold))
+    );
+    assert_eq!(
+        client.try_set_threshold(&4),
+        Err(Ok(Error::InvalidThreshold))
+    );
+
+    apply_after_delay(&env, &contract_id, client.set_threshold(&3));
+    assert_eq!(
+        client.try_remove_signer(&credential_id(&env, 3)),
+        Err(Ok(Error::InvalidThreshold))
+    );
+
+    apply_after_delay(&env, &contract_id, client.set_threshold(&2));
+    apply_after_delay(
+        &env,
+        &contract_id,
+        client.remove_signer(&credential_id(&env, 3)),
+    );
+    assert_eq!(client.get_threshold(), 2);
+    assert_eq!(client.list_signers().len(), 2);
+}
+
+// Session keys
+
+fn ed25519_key(seed: u8) -> ed25519_dalek::SigningKey {
+    ed25519_dalek::SigningKey::from_bytes(&[seed; 32])
+}
+
+fn call(env: &Env, contract: &Address, fn_name: Symbol) -> Context {
+    Context::Contract(ContractContext {
+        contract: contract.clone(),
+        fn_name,
+        args: vec![env],
+    })
+}
+
+/// A session key that may only call `pay` on `contract` for the next hour
+fn session_key(env: &Env, key: SignerKey, contract: &Address) -> SessionKey {
+    SessionKey {
+        key,
+        expires_at: env.ledger().timestamp() + 3600,
+        contracts: vec![env, contract.clone()],
+        functions: vec![env, symbol_short!("pay")],
+    }
+}
+
+#[test]
+fn test_session_key_ed25519() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = setup(&env, 1);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let trip = Address::generate(&env);
+    let payload = fixed_bytes(&env, 1);
+
+    let key = ed25519_key(7);
+    let id = Bytes::from_slice(&env, b"session-1");
+    let public_key = BytesN::from_array(&env, &key.verifying_key().to_bytes());
+    client.add_session_key(
+        &id,
+        &session_key(&env, SignerKey::Ed25519(public_key), &trip),
+    );
+
+    let signature = SignerSignature {
+        credential_id: id.clone(),
+        signature: Signature::Ed25519(BytesN::from_array(
+            &env,
+            &key.sign(&payload.to_array()).to_bytes(),
+        )),
+    };
+    let signatures = vec![&env, signature];
+
+    assert_eq!(
+        check_auth_for(
+            &env,
+            &contract_id,
+            &payload,
+            signatures.clone(),
+            vec![&env, call(&env, &trip, symbol_short!("pay"))]
+        ),
+        Ok(())
+    );
+
+    // Outside the allowed functions or contracts
+    assert_eq!(
+        check_auth_for(
+            &env,
+            &contract_id,
+            &payload,
+            signatures.clone(),
+            vec![&env, call(&env, &trip, symbol_short!("refund"))]
+        ),
+        Err(Ok(Error::SessionKeyNotAllowed))
+    );
+    assert_eq!(
+        check_auth_for(
+            &env,
+            &contract_id,
+            &payload,
+            signatures.clone(),
+            vec![
+                &env,
+                call(&env, &trip, symbol_short!("pay")),
+                call(&env, &Address::generate(&env), symbol_short!("pay"))
+            ]
+        ),
+        Err(Ok(Error::SessionKeyNotAllowed))
+    );
+
+    // Expired
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3600);
+    assert_eq!(
+        check_auth_for(
+            &env,
+            &contract_id,
+            &payload,
+            signatures,
+            vec![&env, call(&env, &trip, symbol_short!("pay"))]
+        ),
+        Err(Ok(Error::SessionKeyExpired))
+    );
+}
+
+#[test]
+fn test_session_key_secp256r1_and_revoke() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = setup_two_of_three(&env);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let trip = Address::generate(&env);
+    let payload = fixed_bytes(&env, 1);
+
+    let id = Bytes::from_slice(&env, b"session-2");
+    let public_key = public_key(&env, &signing_key(9));
+    client.add_session_key(
+        &id,
+        &session_key(&env, SignerKey::Secp256r1(public_key), &trip),
+    );
+    assert!(client.get_session_key(&id).is_some());
+
+    // The session key alone is enough for in-scope calls, even with a 2-of-3 policy
+    let signature = SignerSignature {
+        credential_id: id.clone(),
+        signature: Signature::Secp256r1(raw_signature(&env, &signing_key(9), &payload)),
+    };
+    let context = vec![&env, call(&env, &trip, symbol_short!("pay"))];
+    assert_eq!(
+        check_auth_for(
+            &env,
+            &contract_id,
+            &payload,
+            vec![&env, signature.clone()],
+            context.clone()
+        ),
+        Ok(())
+    );
+
+    client.revoke_session_key(&id);
+    assert_eq!(client.get_session_key(&id), None);
+    assert_eq!(
+        check_auth_for(&env, &contract_id, &payload, vec![&env, signature], context),
+        Err(Ok(Error::SignerNotFound))
+    );
+}
+
+#[test]
+fn test_add_session_key_rejects_invalid_scope() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = setup(&env, 1);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let id = Bytes::from_slice(&env, b"session-3");
+    let key = SignerKey::Secp256r1(public_key(&env, &signing_key(9)));
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = 1000);
+    let mut expired = session_key(&env, key.clone(), &Address::generate(&env));
+    expired.expires_at = 1000;
+    assert_eq!(
+        client.try_add_session_key(&id, &expired),
+        Err(Ok(Error::InvalidSessionKey))
+    );
+
+    let mut unscoped = session_key(&env, key.clone(), &Address::generate(&env));
+    unscoped.functions = vec![&env];
+    assert_eq!(
+        client.try_add_session_key(&id, &unscoped),
+        Err(Ok(Error::InvalidSessionKey))
+    );
+
+    // A session key must never be able to manage the account itself
+    let own = session_key(&env, key, &contract_id);
+    assert_eq!(
+        client.try_add_session_key(&id, &own),
+        Err(Ok(Error::InvalidSessionKey))
+    );
+}
+
+// Per-token spending limits
+
+fn transfer(env: &Env, token: &Address, from: &Address, amount: i128) -> Context {
+    Context::Contract(ContractContext {
+        contract: token.clone(),
+        fn_name: symbol_short!("transfer"),
+        args: vec![
+            env,
+            from.into_val(env),
+            Address::generate(env).into_val(env),
+            amount.into_val(env),
+        ],
+    })
+}
+
+#[test]
+fn test_storage_ttl_extended() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (contract_id, guardians) = setup_guardians(&env);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let token = Address::generate(&env);
+    let payload = fixed_bytes(&env, 1);
+    let max_ttl = env.as_contract(&contract_id, || env.storage().max_ttl());
+    let ttl =
+        |key: &DataKey| env.as_contract(&contract_id, || env.storage().persistent().get_ttl(key));
+    let instance_ttl = || env.as_contract(&contract_id, || env.storage().instance().get_ttl());
+
+    let id = Bytes::from_slice(&env, b"session-4");
+    client.add_session_key(&id, &session_key(&env, passkey(&env, 9), &token));
+    let session_entry = env.as_contract(&contract_id, || {
+        PasskeyAccount::session_key_entry(&env, id.clone())
+    });
+    client.set_spending_limit(
+        &token,
+        &SpendingLimit {
+            daily: 100,
+            weekly: 100,
+        },
+    );
+    assert_eq!(
+        check_auth_for(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[1], &payload),
+            vec![&env, transfer(&env, &token, &contract_id, 10)]
+        ),
+        Ok(())
+    );
+    client.initiate_recovery(
+        &vec![&env, guardians.get(0).unwrap(), guardians.get(1).unwrap()],
+        &public_key(&env, &signing_key(2)),
+        &credential_id(&env, 2),
+    );
+
+    let limit_entry = DataKey::SpendingLimit(token.clone());
+    let spends_entry = DataKey::Spends(token.clone());
+    assert_eq!(ttl(&session_entry), max_ttl);
+    assert_eq!(ttl(&limit_entry), max_ttl);
+    assert_eq!(ttl(&spends_entry), max_ttl);
+    assert_eq!(instance_ttl(), max_ttl);
+
+    // Reading the entries extends them again
+    env.ledger()
+        .with_mut(|ledger| ledger.sequence_number += 1000);
+    assert_eq!(ttl(&session_entry), max_ttl - 1000);
+    assert_eq!(instance_ttl(), max_ttl - 1000);
+    assert!(client.get_session_key(&id).is_some());
+    assert!(client.get_spending_limit(&token).is_some());
+    assert_eq!(client.get_spent(&token), (10, 10));
+    assert!(client.get_recovery().is_some());
+    assert_eq!(ttl(&session_entry), max_ttl);
+    assert_eq!(ttl(&limit_entry), max_ttl);
+    assert_eq!(ttl(&spends_entry), max_ttl);
+    assert_eq!(instance_ttl(), max_ttl);
+}
+
+#[test]
+fn test_spending_limit_rolling_windows() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = setup(&env, 1);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let token = Address::generate(&env);
+    let payload = fixed_bytes(&env, 1);
+
+    client.set_spending_limit(
+        &token,
+        &SpendingLimit {
+            daily: 100,
+            weekly: 250,
+        },
+    );
+
+    let pay = |amount: i128| {
+        check_auth_for(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[1], &payload),
+            vec![&env, transfer(&env, &token, &contract_id, amount)],
+        )
+    };
+
+    assert_eq!(pay(60), Ok(()));
+    assert_eq!(pay(50), Err(Ok(Error::SpendingLimitExceeded)));
+    assert_eq!(pay(40), Ok(()));
+    assert_eq!(client.get_spent(&token), (100, 100));
+
+    // The daily window rolls over, the weekly one keeps counting
+    env.ledger().with_mut(|ledger| ledger.timestamp += DAY);
+    assert_eq!(client.get_spent(&token), (0, 100));
+    assert_eq!(pay(100), Ok(()));
+    env.ledger().with_mut(|ledger| ledger.timestamp += DAY);
+    assert_eq!(pay(60), Err(Ok(Error::SpendingLimitExceeded)));
+    assert_eq!(pay(50), Ok(()));
+    assert_eq!(client.get_spent(&token), (50, 250));
+
+    // Other tokens are not limited
+    assert_eq!(
+        check_auth_for(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[1], &payload),
+            vec![
+                &env,
+                transfer(&env, &Address::generate(&env), &contract_id, 1_000)
+            ],
+        ),
+        Ok(())
+    );
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += WEEK);
+    assert_eq!(client.get_spent(&token), (0, 0));
+}
+
+#[test]
+fn test_spending_limit_additional_signer() {
+    let env = Env::default();
+    let contract_id = setup_two_of_three(&env);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let token = Address::generate(&env);
+    let payload = fixed_bytes(&env, 1);
+
+    client.set_spending_limit(
+        &token,
+        &SpendingLimit {
+            daily: 100,
+            weekly: 100,
+        },
+    );
+    let context = vec![&env, transfer(&env, &token, &contract_id, 500)];
+
+    assert_eq!(
+        check_auth_for(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[1, 2], &payload),
+            context.clone()
+        ),
+        Err(Ok(Error::SpendingLimitExceeded))
+    );
+    assert_eq!(
+        check_auth_for(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[1, 2, 3], &payload),
+            context
+        ),
+        Ok(())
+    );
+
+    assert_eq!(
+        client.try_set_spending_limit(
+            &token,
+            &SpendingLimit {
+                daily: 200,
+                weekly: 100
+            }
+        ),
+        Err(Ok(Error::InvalidSpendingLimit))
+    );
+    apply_after_delay(&env, &contract_id, client.remove_spending_limit(&token));
+    assert_eq!(client.get_spending_limit(&token), None);
+}
+
+#[test]
+fn test_loosening_spending_limit_waits_for_rotation_delay() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = setup(&env, 1);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let token = Address::generate(&env);
+    let payload = fixed_bytes(&env, 1);
+    let limit = |daily: i128, weekly: i128| SpendingLimit { daily, weekly };
+    let pay = |amount: i128| {
+        check_auth_for(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[1], &payload),
+            vec![&env, transfer(&env, &token, &contract_id, amount)],
+        )
+    };
+
+    // A first or lower limit applies at once
+    assert_eq!(client.set_spending_limit(&token, &limit(100, 200)), None);
+    assert_eq!(client.set_spending_limit(&token, &limit(50, 200)), None);
+    assert_eq!(client.get_spending_limit(&token), Some(limit(50, 200)));
+
+    // A stolen passkey cannot raise or remove the limit and drain the token
+    let raise = client
+        .set_spending_limit(&token, &limit(1_000, 1_000))
+        .unwrap();
+    let remove = client.remove_spending_limit(&token);
+    assert_eq!(client.get_spending_limit(&token), Some(limit(50, 200)));
+    assert_eq!(pay(500), Err(Ok(Error::SpendingLimitExceeded)));
+    assert_eq!(
+        client.try_apply_change(&remove),
+        Err(Ok(Error::ChangeTimelocked))
+    );
+
+    // The owner cancels the raise, the removal goes through after the delay
+    client.cancel_change(&raise);
+    apply_after_delay(&env, &contract_id, remove);
+    assert_eq!(client.get_spending_limit(&token), None);
+    assert_eq!(pay(500), Ok(()));
+}
+
+// Guardian social recovery
+
+/// An account recoverable by two of three guardians after a one-day timelock
+fn setup_guardians(env: &Env) -> (Address, Vec<Address>) {
+    env.mock_all_auths();
+    let contract_id = setup(env, 1);
+    let client = PasskeyAccountClient::new(env, &contract_id);
+    let guardians = vec![
+        env,
+        Address::generate(env),
+        Address::generate(env),
+        Address::generate(env),
+    ];
+    apply_after_delay(
+        env,
+        &contract_id,
+        client.set_guardians(&GuardianConfig {
+            guardians: guardians.clone(),
+            quorum: 2,
+            delay: DAY,
+        }),
+    );
+    (contract_id, guardians)
+}
+
+#[test]
+fn test_recovery_by_guardians() {
+    let env = Env::default();
+    let (contract_id, guardians) = setup_guardians(&env);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let payload = fixed_bytes(&env, 1);
+
+    let approvals = vec![&env, guardians.get(0).unwrap(), guardians.get(2).unwrap()];
+    client.initiate_recovery(
+        &approvals,
+        &public_key(&env, &signing_key(2)),
+        &credential_id(&env, 2),
+    );
+    assert_eq!(env.auths().len(), 2);
+    assert_eq!(
+        last_events(&env, 1),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("recovery"), symbol_short!("started")).into_val(&env),
+                (credential_id(&env, 2), env.ledger().timestamp() + DAY).into_val(&env)
+            ),
+        ]
+    );
+
+    // The lost passkey still works until the timelock expires
+    assert_eq!(
+        client.try_finalize_recovery(),
+        Err(Ok(Error::RecoveryTimelocked))
+    );
+    env.ledger().with_mut(|ledger| ledger.timestamp += DAY);
+    client.finalize_recovery();
+    assert_eq!(
+        last_events(&env, 2),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("recovery"), symbol_short!("finished")).into_val(&env),
+                credential_id(&env, 2).into_val(&env)
+            ),
+            (
+                contract_id.clone(),
+                (symbol_short!("owner"), symbol_short!("changed")).into_val(&env),
+                credential_id(&env, 2).into_val(&env)
+            ),
+        ]
+    );
+    assert_eq!(client.get_recovery(), None);
+
+    assert_eq!(
+        check_auth(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[1], &payload)
+        ),
+        Err(Ok(Error::SignerNotFound))
+    );
+    assert_eq!(
+        check_auth(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[2], &payload)
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_session_keys_revoked_by_recovery_and_rotation() {
+    let env = Env::default();
+    let (contract_id, guardians) = setup_guardians(&env);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let trip = Address::generate(&env);
+    let payload = fixed_bytes(&env, 1);
+    let context = vec![&env, call(&env, &trip, symbol_short!("pay"))];
+
+    // A week-long session key that outlives the recovery timelock
+    env.mock_all_auths();
+    let id = Bytes::from_slice(&env, b"session-3");
+    let mut key = session_key(&env, passkey(&env, 9), &trip);
+    key.expires_at = env.ledger().timestamp() + WEEK;
+    client.add_session_key(&id, &key);
+    let signatures = vec![
+        &env,
+        SignerSignature {
+            credential_id: id.clone(),
+            signature: Signature::Secp256r1(raw_signature(&env, &signing_key(9), &payload)),
+        },
+    ];
+    assert_eq!(
+        check_auth_for(
+            &env,
+            &contract_id,
+            &payload,
+            signatures.clone(),
+            context.clone()
+        ),
+        Ok(())
+    );
+
+    client.initiate_recovery(
+        &vec![&env, guardians.get(0).unwrap(), guardians.get(1).unwrap()],
+        &public_key(&env, &signing_key(2)),
+        &credential_id(&env, 2),
+    );
+    env.ledger().with_mut(|ledger| ledger.timestamp += DAY);
+    client.finalize_recovery();
+
+    // The key could belong to whoever stole the lost passkey
+    assert_eq!(client.get_session_key(&id), None);
+    assert_eq!(
+        check_auth_for(
+            &env,
+            &contract_id,
+            &payload,
+            signatures.clone(),
+            context.clone()
+        ),
+        Err(Ok(Error::SignerNotFound))
+    );
+
+    // The same applies to a rotation
+    client.add_session_key(&id, &key);
+    assert!(client.get_session_key(&id).is_some());
+    client.propose_owner(&public_key(&env, &signing_key(3)), &credential_id(&env, 3));
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp += DEFAULT_ROTATION_DELAY);
+    client.apply_rotation();
+    assert_eq!(client.get_session_key(&id), None);
+    assert_eq!(
+        check_auth_for(&env, &contract_id, &payload, signatures, context),
+        Err(Ok(Error::SignerNotFound))
+    );
+}
+
+#[test]
+fn test_recovery_requires_guardian_quorum() {
+    let env = Env::default();
+    let (contract_id, guardians) = setup_guardians(&env);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let new_key = public_key(&env, &signing_key(2));
+    let new_id = credential_id(&env, 2);
+
+    let guardian = guardians.get(0).unwrap();
+    assert_eq!(
+        client.try_initiate_recovery(
+            &vec![&env, guardian.clone(), guardian.clone()],
+            &new_key,
+            &new_id
+        ),
+        Err(Ok(Error::GuardianQuorumNotMet))
+    );
+    assert_eq!(
+        client.try_initiate_recovery(
+            &vec![&env, guardian, Address::generate(&env)],
+            &new_key,
+            &new_id
+        ),
+        Err(Ok(Error::NotGuardian))
+    );
+    assert_eq!(client.get_recovery(), None);
+
+    assert_eq!(
+        client.try_set_guardians(&GuardianConfig {
+            guardians,
+            quorum: 4,
+            delay: DAY
+        }),
+        Err(Ok(Error::InvalidGuardians))
+    );
+}
+
+#[test]
+fn test_owner_cancels_recovery() {
+    let env = Env::default();
+    let (contract_id, guardians) = setup_guardians(&env);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let payload = fixed_bytes(&env, 1);
+
+    let approvals = vec![&env, guardians.get(0).unwrap(), guardians.get(1).unwrap()];
+    client.initiate_recovery(
+        &approvals,
+        &public_key(&env, &signing_key(2)),
+        &credential_id(&env, 2),
+    );
+    assert_eq!(
+        client.try_initiate_recovery(
+            &approvals,
+            &public_key(&env, &signing_key(3)),
+            &credential_id(&env, 3)
+        ),
+        Err(Ok(Error::RecoveryAlreadyPending))
+    );
+
+    client.cancel_recovery();
+    env.ledger().with_mut(|ledger| ledger.timestamp += DAY);
+    assert_eq!(
+        client.try_finalize_recovery(),
+        Err(Ok(Error::RecoveryNotPending))
+    );
+    assert_eq!(
+        check_auth(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[1], &payload)
+        ),
+        Ok(())
+    );
+}
+
+// Timelocked key rotation
+
+#[test]
+fn test_rotation_applies_after_delay() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = setup_two_of_three(&env);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let payload = fixed_bytes(&env, 1);
+
+    client.propose_owner(&public_key(&env, &signing_key(4)), &credential_id(&env, 4));
+    let rotation = client.get_pending_rotation().unwrap();
+    assert_eq!(
+        rotation.effective_at,
+        env.ledger().timestamp() + DEFAULT_ROTATION_DELAY
+    );
+
+    // The current passkeys keep control during the window
+    assert_eq!(
+        client.try_apply_rotation(),
+        Err(Ok(Error::RotationTimelocked))
+    );
+    assert_eq!(
+        check_auth(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[1, 2], &payload)
+        ),
+        Ok(())
+    );
+
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp += DEFAULT_ROTATION_DELAY);
+    client.apply_rotation();
+    assert_eq!(client.get_pending_rotation(), None);
+    assert_eq!(client.get_threshold(), 1);
+    assert_eq!(client.list_signers().len(), 1);
+    assert_eq!(
+        check_auth(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[4], &payload)
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_rotation_cancelled_by_owner() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = setup(&env, 1);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let payload = fixed_bytes(&env, 1);
+
+    apply_after_delay(&env, &contract_id, client.set_rotation_delay(&(3 * DAY)));
+    assert_eq!(client.get_rotation_delay(), 3 * DAY);
+    assert_eq!(
+        client.try_set_rotation_delay(&60),
+        Err(Ok(Error::InvalidRotationDelay))
+    );
+
+    client.propose_owner(&public_key(&env, &signing_key(2)), &credential_id(&env, 2));
+    assert_eq!(
+        client.try_propose_owner(&public_key(&env, &signing_key(3)), &credential_id(&env, 3)),
+        Err(Ok(Error::RotationAlreadyPending))
+    );
+
+    client.cancel_rotation();
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3 * DAY);
+    assert_eq!(
+        client.try_apply_rotation(),
+        Err(Ok(Error::RotationNotPending))
+    );
+    assert_eq!(
+        client.try_cancel_rotation(),
+        Err(Ok(Error::RotationNotPending))
+    );
+    assert_eq!(
+        check_auth(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[1], &payload)
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_rotation_requires_account_auth() {
+    let env = Env::default();
+    let contract_id = setup(&env, 1);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+
+    assert!(client
+        .try_propose_owner(&public_key(&env, &signing_key(2)), &credential_id(&env, 2))
+        .is_err());
+    assert_eq!(client.get_pending_rotation(), None);
+}
+
+#[test]
+fn test_account_changes_wait_for_rotation_delay() {
+    let env = Env::default();
+    let contract_id = setup_two_of_three(&env);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let payload = fixed_bytes(&env, 1);
+
+    // A stolen passkey tries to take over with every admin entry point
+    let add = client.add_signer(&credential_id(&env, 4), &passkey(&env, 4), &2, &vec![&env]);
+    assert_eq!(
+        last_events(&env, 1),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("change"), symbol_short!("proposed")).into_val(&env),
+                (add, env.ledger().timestamp() + DEFAULT_ROTATION_DELAY).into_val(&env)
+            ),
+        ]
+    );
+    let remove = client.remove_signer(&credential_id(&env, 1));
+    let threshold = client.set_threshold(&1);
+    let guardians = client.set_guardians(&GuardianConfig {
+        guardians: vec![&env, Address::generate(&env)],
+        quorum: 1,
+        delay: 0,
+    });
+    let delay = client.set_rotation_delay(&MIN_ROTATION_DELAY);
+    let upgrade = client.upgrade(&fixed_bytes(&env, 2));
+
+    // Nothing changes before the rotation delay has passed
+    for id in [add, remove, threshold, guardians, delay, upgrade] {
+        assert_eq!(
+            client.try_apply_change(&id),
+            Err(Ok(Error::ChangeTimelocked))
+        );
+    }
+    assert_eq!(client.list_pending_changes().len(), 6);
+    assert_eq!(client.list_signers().len(), 3);
+    assert_eq!(client.get_threshold(), 2);
+    assert_eq!(client.get_guardians(), None);
+    assert_eq!(
+        check_auth(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[4], &payload)
+        ),
+        Err(Ok(Error::SignerNotFound))
+    );
+
+    // The lower delay does not apply to rotations proposed in the meantime
+    assert_eq!(client.get_rotation_delay(), DEFAULT_ROTATION_DELAY);
+    client.propose_owner(&public_key(&env, &signing_key(5)), &credential_id(&env, 5));
+    assert_eq!(
+        client.get_pending_rotation().unwrap().effective_at,
+        env.ledger().timestamp() + DEFAULT_ROTATION_DELAY
+    );
+
+    // The owner notices and cancels some of the changes
+    client.cancel_change(&add);
+    client.cancel_change(&upgrade);
+    assert_eq!(
+        client.try_cancel_change(&add),
+        Err(Ok(Error::ChangeNotPending))
+    );
+    assert_eq!(
+        client.try_apply_change(&add),
+        Err(Ok(Error::ChangeNotPending))
+    );
+
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp += DEFAULT_ROTATION_DELAY);
+    for id in [remove, threshold, guardians, delay] {
+        client.apply_change(&id);
+    }
+    assert!(client.list_pending_changes().is_empty());
+    assert_eq!(client.list_signers().len(), 2);
+    assert_eq!(client.get_threshold(), 1);
+    assert!(client.get_guardians().is_some());
+    assert_eq!(client.get_rotation_delay(), MIN_ROTATION_DELAY);
+
+    // Changes proposed by the previous signers do not survive a rotation
+    let pending = client.set_threshold(&2);
+    client.apply_rotation();
+    assert!(client.list_pending_changes().is_empty());
+    assert_eq!(
+        client.try_apply_change(&pending),
+        Err(Ok(Error::ChangeNotPending))
+    );
+}
+
+// Call allow-list and deny-list
+
+#[test]
+fn test_call_policy() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = setup(&env, 1);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let payload = fixed_bytes(&env, 1);
+    let loan = Address::generate(&env);
+    let trip = Address::generate(&env);
+    let usdc = Address::generate(&env);
+    let other = Address::generate(&env);
+
+    let mut allowed = Map::new(&env);
+    allowed.set(loan.clone(), vec![&env]);
+    allowed.set(trip.clone(), vec![&env]);
+    allowed.set(usdc.clone(), vec![&env, symbol_short!("transfer")]);
+    client.set_call_policy(&CallPolicy {
+        allowed,
+        denied: vec![&env],
+    });
+
+    let authorize = |context: Context| {
+        check_auth_for(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[1], &payload),
+            vec![&env, context],
+        )
+    };
+
+    assert_eq!(authorize(call(&env, &loan, symbol_short!("repay"))), Ok(()));
+    assert_eq!(authorize(call(&env, &trip, symbol_short!("pay"))), Ok(()));
+    assert_eq!(
+        authorize(call(&env, &usdc, symbol_short!("transfer"))),
+        Ok(())
+    );
+    assert_eq!(
+        authorize(call(&env, &contract_id, Symbol::new(&env, "add_signer"))),
+        Ok(())
+    );
+
+    // Everything else is rejected by default
+    assert_eq!(
+        authorize(call(&env, &usdc, symbol_short!("approve"))),
+        Err(Ok(Error::CallNotAllowed))
+    );
+    assert_eq!(
+        authorize(call(&env, &other, symbol_short!("pay"))),
+        Err(Ok(Error::CallNotAllowed))
+    );
+
+    // The deny-list wins over the allow-list
+    let mut policy = client.get_call_policy().unwrap();
+    policy.denied.push_back(trip.clone());
+    client.set_call_policy(&policy);
+    assert_eq!(
+        authorize(call(&env, &trip, symbol_short!("pay"))),
+        Err(Ok(Error::CallNotAllowed))
+    );
+
+    apply_after_delay(&env, &contract_id, client.remove_call_policy());
+    assert_eq!(authorize(call(&env, &other, symbol_short!("pay"))), Ok(()));
+}
+
+#[test]
+fn test_loosening_call_policy_waits_for_rotation_delay() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = setup(&env, 1);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let payload = fixed_bytes(&env, 1);
+    let usdc = Address::generate(&env);
+    let drain = || {
+        check_auth_for(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[1], &payload),
+            vec![&env, call(&env, &usdc, symbol_short!("approve"))],
+        )
+    };
+    let policy = |functions: Vec<Symbol>, denied: Vec<Address>| {
+        let mut allowed = Map::new(&env);
+        allowed.set(usdc.clone(), functions);
+        CallPolicy { allowed, denied }
+    };
+
+    // A first policy, or one that only narrows it, applies at once
+    let all = policy(vec![&env], vec![&env]);
+    let transfer_only = policy(vec![&env, symbol_short!("transfer")], vec![&env]);
+    assert_eq!(client.set_call_policy(&all), None);
+    assert_eq!(client.set_call_policy(&transfer_only), None);
+    let denied = policy(
+        vec![&env, symbol_short!("transfer")],
+        vec![&env, Address::generate(&env)],
+    );
+    assert_eq!(client.set_call_policy(&denied), None);
+    assert_eq!(client.get_call_policy(), Some(denied));
+
+    // A stolen passkey cannot widen or remove the policy at once
+    let widen = client.set_call_policy(&all).unwrap();
+    let undeny = client.set_call_policy(&transfer_only).unwrap();
+    let remove = client.remove_call_policy();
+    assert_eq!(drain(), Err(Ok(Error::CallNotAllowed)));
+    assert_eq!(
+        client.try_apply_change(&widen),
+        Err(Ok(Error::ChangeTimelocked))
+    );
+
+    // The owner cancels the removal, the wider policy applies after the delay
+    client.cancel_change(&remove);
+    client.cancel_change(&undeny);
+    apply_after_delay(&env, &contract_id, widen);
+    assert_eq!(client.get_call_policy(), Some(all));
+    assert_eq!(drain(), Ok(()));
+}
+
+// Ed25519 backup signer
+
+/// Register the Stellar keypair derived from `seed` as a backup signer that
+/// can only propose a new owner passkey
+fn add_backup_key(env: &Env, contract_id: &Address, seed: u8) -> Bytes {
+    let client = PasskeyAccountClient::new(env, contract_id);
+    let id = Bytes::from_slice(env, b"stellar-backup");
+    let public_key = BytesN::from_array(env, &ed25519_key(seed).verifying_key().to_bytes());
+    apply_after_delay(
+        env,
+        contract_id,
+        client.add_signer(
+            &id,
+            &SignerKey::Ed25519(public_key),
+            &1,
+            &vec![env, Symbol::new(env, "propose_owner")],
+        ),
+    );
+    id
+}
+
+fn sign_ed25519(env: &Env, id: &Bytes, seed: u8, payload: &BytesN<32>) -> SignerSignature {
+    SignerSignature {
+        credential_id: id.clone(),
+        signature: Signature::Ed25519(BytesN::from_array(
+            env,
+            &ed25519_key(seed).sign(&payload.to_array()).to_bytes(),
+        )),
+    }
+}
+
+#[test]
+fn test_ed25519_backup_signer_limited_to_recovery() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = setup(&env, 1);
+    let id = add_backup_key(&env, &contract_id, 8);
+    let payload = fixed_bytes(&env, 1);
+    let signatures = vec![&env, sign_ed25519(&env, &id, 8, &payload)];
+
+    assert_eq!(
+        check_auth_for(
+            &env,
+            &contract_id,
+            &payload,
+            signatures.clone(),
+            vec![
+                &env,
+                call(&env, &contract_id, Symbol::new(&env, "propose_owner"))
+            ]
+        ),
+        Ok(())
+    );
+    assert_eq!(
+        check_auth_for(
+            &env,
+            &contract_id,
+            &payload,
+            signatures.clone(),
+            vec![
+                &env,
+                call(&env, &contract_id, Symbol::new(&env, "add_signer"))
+            ]
+        ),
+        Err(Ok(Error::SignerNotAllowed))
+    );
+    assert_eq!(
+        check_auth_for(
+            &env,
+            &contract_id,
+            &payload,
+            signatures,
+            vec![
+                &env,
+                call(&env, &Address::generate(&env), symbol_short!("transfer"))
+            ]
+        ),
+        Err(Ok(Error::SignerNotAllowed))
+    );
+
+    // The passkey is not restricted
+    assert_eq!(
+        check_auth_for(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[1], &payload),
+            vec![
+                &env,
+                call(&env, &Address::generate(&env), symbol_short!("transfer"))
+            ]
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_signature_scheme_must_match_signer() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = setup(&env, 1);
+    let id = add_backup_key(&env, &contract_id, 8);
+    let payload = fixed_bytes(&env, 1);
+
+    // An ed25519 signature claiming to come from the passkey
+    let mut signature = sign_ed25519(&env, &id, 8, &payload);
+    signature.credential_id = credential_id(&env, 1);
+    assert_eq!(
+        check_auth(&env, &contract_id, &payload, vec![&env, signature]),
+        Err(Ok(Error::InvalidSignature))
+    );
+
+    // A secp256r1 signature claiming to come from the backup key
+    let mut signature = sign(&env, 1, &payload);
+    signature.credential_id = id;
+    assert_eq!(
+        check_auth(&env, &contract_id, &payload, vec![&env, signature]),
+        Err(Ok(Error::InvalidSignature))
+    );
+}
+
+// Upgrades and storage migrations
+
+#[test]
+fn test_migrate_legacy_single_owner() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PasskeyAccount);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let payload = fixed_bytes(&env, 1);
+
+    // Storage as written by the original single-owner contract
+    env.as_contract(&contract_id, || {
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Owner, &public_key(&env, &signing_key(1)));
+        storage.set(&DataKey::CredentialId, &credential_id(&env, 1));
+    });
+    assert_eq!(client.get_version(), 1);
+    assert_eq!(
+        client.try_init(
+            &public_key(&env, &signing_key(2)).into(),
+            &credential_id(&env, 2)
+        ),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+
+    assert_eq!(client.migrate(), SCHEMA_VERSION);
+    assert_eq!(client.get_version(), SCHEMA_VERSION);
+    assert_eq!(client.get_threshold(), 1);
+    assert_eq!(
+        client
+            .list_signers()
+            .get(credential_id(&env, 1))
+            .map(|signer| signer.key),
+        Some(passkey(&env, 1))
+    );
+    assert_eq!(
+        check_auth(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[1], &payload)
+        ),
+        Ok(())
+    );
+
+    // Migrating again is a no-op
+    assert_eq!(client.migrate(), SCHEMA_VERSION);
+    assert_eq!(client.list_signers().len(), 1);
+}
+
+#[test]
+fn test_migrate_rejects_newer_schema() {
+    let env = Env::default();
+    let contract_id = setup(&env, 1);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    assert_eq!(client.get_version(), SCHEMA_VERSION);
+
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .instance()
+            .set(&DataKey::Version, &(SCHEMA_VERSION + 1));
+    });
+    assert_eq!(client.try_migrate(), Err(Ok(Error::UnsupportedVersion)));
+}
+
+#[test]
+fn test_upgrade_requires_account_auth() {
+    let env = Env::default();
+    let contract_id = setup(&env, 1);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+
+    assert!(client.try_upgrade(&fixed_bytes(&env, 1)).is_err());
+    assert_eq!(
+        PasskeyAccountClient::new(&env, &env.register_contract(None, PasskeyAccount))
+            .try_upgrade(&fixed_bytes(&env, 2)),
+        Err(Ok(Error::NotInitialized))
+    );
+}
+
+// Account events
+
+/// The last `n` events published in the test environment
+fn last_events(env: &Env, n: u32) -> Vec<(Address, Vec<Val>, Val)> {
+    let events = env.events().all();
+    events.slice(events.len() - n..)
+}
+
+#[test]
+fn test_lifecycle_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, PasskeyAccount);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+
+    client.init(
+        &public_key(&env, &signing_key(1)).into(),
+        &credential_id(&env, 1),
+    );
+    assert_eq!(
+        last_events(&env, 1),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("account"), symbol_short!("init")).into_val(&env),
+                credential_id(&env, 1).into_val(&env)
+            ),
+        ]
+    );
+
+    apply_after_delay(
+        &env,
+        &contract_id,
+        client.add_signer(&credential_id(&env, 2), &passkey(&env, 2), &3, &vec![&env]),
+    );
+    assert_eq!(
+        last_events(&env, 1),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("signer"), symbol_short!("added")).into_val(&env),
+                (credential_id(&env, 2), 3u32).into_val(&env)
+            ),
+        ]
+    );
+
+    apply_after_delay(
+        &env,
+        &contract_id,
+        client.remove_signer(&credential_id(&env, 1)),
+    );
+    assert_eq!(
+        last_events(&env, 1),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("signer"), symbol_short!("removed")).into_val(&env),
+                credential_id(&env, 1).into_val(&env)
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_check_auth_emits_auth_used() {
+    let env = Env::default();
+    let contract_id = setup_two_of_three(&env);
+    let payload = fixed_bytes(&env, 1);
+
+    assert_eq!(
+        check_auth(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[3, 1], &payload)
+        ),
+        Ok(())
+    );
+    let used: std::vec::Vec<_> = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(_, topics, _)| {
+            *topics == (symbol_short!("auth"), symbol_short!("used")).into_val(&env)
+        })
+        .map(|(_, _, data)| Bytes::try_from_val(&env, &data).unwrap())
+        .collect();
+    assert_eq!(used, [credential_id(&env, 1), credential_id(&env, 3)]);
+}
+
+#[test]
+fn test_policy_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = setup(&env, 1);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let token = Address::generate(&env);
+    let last_policy_event = |what: Symbol, data: Val| {
+        assert_eq!(
+            last_events(&env, 1),
+            vec![
+                &env,
+                (
+                    contract_id.clone(),
+                    (symbol_short!("policy"), what).into_val(&env),
+                    data
+                ),
+            ]
+        );
+    };
+
+    client.set_spending_limit(
+        &token,
+        &SpendingLimit {
+            daily: 100,
+            weekly: 250,
+        },
+    );
+    last_policy_event(
+        symbol_short!("limit_set"),
+        (token.clone(), 100i128, 250i128).into_val(&env),
+    );
+
+    apply_after_delay(&env, &contract_id, client.remove_spending_limit(&token));
+    last_policy_event(symbol_short!("limit_del"), token.into_val(&env));
+
+    let mut allowed = Map::new(&env);
+    allowed.set(Address::generate(&env), vec![&env]);
+    client.set_call_policy(&CallPolicy {
+        allowed,
+        denied: vec![&env, Address::generate(&env), Address::generate(&env)],
+    });
+    last_policy_event(symbol_short!("calls_set"), (1u32, 2u32).into_val(&env));
+
+    apply_after_delay(&env, &contract_id, client.remove_call_policy());
+    last_policy_event(symbol_short!("calls_del"), ().into_val(&env));
+
+    apply_after_delay(&env, &contract_id, client.set_rotation_delay(&(2 * DAY)));
+    last_policy_event(symbol_short!("rot_delay"), (2 * DAY).into_val(&env));
+}
+
+// Public key validation
+
+/// SEC1 encoding of the passkey derived from `seed`
+fn sec1_key(env: &Env, seed: u8, compress: bool) -> Bytes {
+    let point = signing_key(seed).verifying_key().to_encoded_point(compress);
+    Bytes::from_slice(env, point.as_bytes())
+}
+
+#[test]
+fn test_init_accepts_all_key_encodings() {
+    let env = Env::default();
+    let payload = fixed_bytes(&env, 1);
+
+    for key in [
+        sec1_key(&env, 1, true),
+        sec1_key(&env, 1, false),
+        public_key(&env, &signing_key(1)).into(),
+    ] {
+        let contract_id = env.register_contract(None, PasskeyAccount);
+        let client = PasskeyAccountClient::new(&env, &contract_id);
+        client.init(&key, &credential_id(&env, 1));
+
+        // Every encoding is stored as the same X || Y coordinates
+        assert_eq!(
+            client
+                .list_signers()
+                .get(credential_id(&env, 1))
+                .map(|signer| signer.key),
+            Some(passkey(&env, 1))
+        );
+        assert_eq!(
+            check_auth(
+                &env,
+                &contract_id,
+                &payload,
+                signed_by(&env, &[1], &payload)
+            ),
+            Ok(())
+        );
+    }
+}
+
+#[test]
+fn test_init_rejects_invalid_public_key() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PasskeyAccount);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let init = |key: Bytes| client.try_init(&key, &credential_id(&env, 1));
+
+    // Wrong lengths
+    assert_eq!(init(Bytes::new(&env)), Err(Ok(Error::InvalidPublicKey)));
+    assert_eq!(
+        init(Bytes::from_array(&env, &[4u8; 63])),
+        Err(Ok(Error::InvalidPublicKey))
+    );
+
+    // Unknown SEC1 tag
+    let mut key = sec1_key(&env, 1, false);
+    key.set(0, 0x05);
+    assert_eq!(init(key), Err(Ok(Error::InvalidPublicKey)));
+
+    // A point that is not on the curve
+    let mut key = sec1_key(&env, 1, false);
+    key.set(64, key.get(64).unwrap() ^ 1);
+    assert_eq!(init(key), Err(Ok(Error::InvalidPublicKey)));
+
+    // A compressed X coordinate with no point on the curve
+    let mut key = Bytes::from_array(&env, &[0u8; 33]);
+    key.set(0, 0x02);
+    key.set(32, 0x01);
+    assert_eq!(init(key), Err(Ok(Error::InvalidPublicKey)));
+
+    assert_eq!(client.try_list_signers(), Err(Ok(Error::NotInitialized)));
+}
+
+#[test]
+fn test_add_signer_rejects_point_off_curve() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = setup(&env, 1);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+
+    let mut coordinates = public_key(&env, &signing_key(2)).to_array();
+    coordinates[63] ^= 1;
+    assert_eq!(
+        client.try_add_signer(
+            &credential_id(&env, 2),
+            &SignerKey::Secp256r1(BytesN::from_array(&env, &coordinates)),
+            &1,
+            &vec![&env]
+        ),
+        Err(Ok(Error::InvalidPublicKey))
+    );
+}
+
+// COSE_Key public keys
+
+/// CBOR-encode the COSE_Key of the passkey derived from `seed`
+fn cose_key(env: &Env, seed: u8, kty: u8, alg: u8, crv: u8) -> Bytes {
+    let point = signing_key(seed).verifying_key().to_encoded_point(false);
+    let mut key = Bytes::from_array(
+        env,
+        &[0xa5, 0x01, kty, 0x03, alg, 0x20, crv, 0x21, 0x58, 0x20],
+    );
+    key.extend_from_slice(&point.as_bytes()[1..33]);
+    key.extend_from_array(&[0x22, 0x58, 0x20]);
+    key.extend_from_slice(&point.as_bytes()[33..]);
+    key
+}
+
+#[test]
+fn test_init_from_cose_key() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PasskeyAccount);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let payload = fixed_bytes(&env, 1);
+
+    // kty: EC2 (2), alg: ES256 (-7), crv: P-256 (1)
+    client.init(
+        &cose_key(&env, 1, 0x02, 0x26, 0x01),
+        &credential_id(&env, 1),
+    );
+    assert_eq!(
+        client
+            .list_signers()
+            .get(credential_id(&env, 1))
+            .map(|signer| signer.key),
+        Some(passkey(&env, 1))
+    );
+    assert_eq!(
+        check_auth(
+            &env,
+            &contract_id,
+            &payload,
+            signed_by(&env, &[1], &payload)
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_init_rejects_unsupported_cose_key() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PasskeyAccount);
+    let client = PasskeyAccountClient::new(&env, &contract_id);
+    let init = |key: Bytes| client.try_init(&key, &credential_id(&env, 1));
+
+    // kty: OKP (1)
+    assert_eq!(
+        init(cose_key(&env, 1, 0x01, 0x26, 0x01)),
+        Err(Ok(Error::InvalidPublicKey))
+    );
+    // alg: EdDSA (-8)
+    assert_eq!(
+        init(cose_key(&env, 1, 0x02, 0x27, 0x01)),
+        Err(Ok(Error::InvalidPublicKey))
+    );
+    // crv: P-384 (2)
+    assert_eq!(
+        init(cose_key(&env, 1, 0x02, 0x26, 0x02)),
+        Err(Ok(Error::InvalidPublicKey))
+    );
+
+    // Truncated, with trailing bytes, and with a point off the curve
+    let key = cose_key(&env, 1, 0x02, 0x26, 0x01);
+    assert_eq!(
+        init(key.slice(..key.len() - 1)),
+        Err(Ok(Error::InvalidPublicKey))
+    );
+    let mut trailing = key.clone();
+    trailing.push_back(0);
+    assert_eq!(init(trailing), Err(Ok(Error::InvalidPublicKey)));
+    let mut off_curve = key.clone();
+    off_curve.set(key.len() - 1, key.get(key.len() - 1).unwrap() ^ 1);
+    assert_eq!(init(off_curve), Err(Ok(Error::InvalidPublicKey)));
+
+    // Duplicate kty label (map of 6 entries)
+    let mut duplicate = Bytes::from_array(&env, &[0xa6, 0x01, 0x02]);
+    duplicate.append(&key.slice(1..));
+    assert_eq!(init(duplicate), Err(Ok(Error::InvalidPublicKey)));
+}
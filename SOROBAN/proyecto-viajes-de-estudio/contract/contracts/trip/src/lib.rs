@@ -1,7 +1,13 @@
+//! EBAS Trip Contract: the loan contract with "trip" (viajes) semantics
+
+#![no_std]
+
+pub mod types;
+
 use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env, Symbol, Vec};
 
 // Import types directly in this module
-use crate::trip_types::{TripConfig, TripError, TripRecord, TripTransferResult};
+use crate::types::{TripConfig, TripError, TripRecord, TripTransferResult};
 
 // Storage keys
 const CONFIG_KEY: Symbol = symbol_short!("CONFIG");
@@ -23,7 +29,7 @@ impl TripContract {
             return Err(TripError::AlreadyInitialized);
         }
 
-        if min_credit_score < 500 || min_credit_score > 850 {
+        if !(500..=850).contains(&min_credit_score) {
             return Err(TripError::InvalidAmount);
         }
 
@@ -99,11 +105,7 @@ impl TripContract {
             .unwrap_or(Vec::new(&env))
     }
 
-    pub fn deposit_to_pool(
-        env: Env,
-        admin: Address,
-        amount: i128,
-    ) -> Result<i128, TripError> {
+    pub fn deposit_to_pool(env: Env, admin: Address, amount: i128) -> Result<i128, TripError> {
         Self::require_admin(&env, &admin)?;
 
         if amount <= 0 {
@@ -121,12 +123,7 @@ impl TripContract {
         env.storage().instance().get(&POOL_BAL).unwrap_or(0)
     }
 
-    pub fn check_eligibility(
-        env: Env,
-        user: Address,
-        amount: i128,
-        credit_score: u32,
-    ) -> bool {
+    pub fn check_eligibility(env: Env, user: Address, amount: i128, credit_score: u32) -> bool {
         let config = match Self::require_initialized(&env) {
             Ok(c) => c,
             Err(_) => return false,
@@ -195,7 +192,9 @@ impl TripContract {
         env.storage().instance().set(&history_key, &history);
 
         let timestamp_key = (symbol_short!("LAST_LOAN"), user);
-        env.storage().instance().set(&timestamp_key, &trip.timestamp);
+        env.storage()
+            .instance()
+            .set(&timestamp_key, &trip.timestamp);
     }
 }
 
@@ -0,0 +1,1942 @@
+#![no_std]
+use soroban_sdk::{
+    auth::{Context, CustomAccountInterface},
+    contract, contracterror, contractimpl, contracttype,
+    crypto::Hash,
+    symbol_short, Address, Bytes, BytesN, Env, String, Symbol, Vec,
+};
+
+use passkey_types::{authenticator_data, base64_url, cbor, ecdsa, public_key};
+
+#[contract]
+pub struct WebAuthnAccount;
+
+#[contracterror]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Error {
+    NotInited = 1,
+    AlreadyInited = 2,
+    ClientDataJsonChallengeIncorrect = 3,
+    Secp256r1PublicKeyParse = 4,
+    Secp256r1SignatureParse = 5,
+    Secp256r1VerifyFailed = 6,
+    JsonParseError = 7,
+    AuthenticatorDataParse = 8,
+    RpIdHashMismatch = 9,
+    UserNotPresent = 10,
+    UserNotVerified = 11,
+    ClientDataJsonTypeIncorrect = 12,
+    OriginNotAllowed = 13,
+    CrossOriginNotAllowed = 14,
+    SignCountNotIncreased = 15,
+    UnsupportedVersion = 16,
+    AttestationParse = 17,
+    AttestationFormatUnsupported = 18,
+    AttestationNoneNotAllowed = 19,
+    AccountLocked = 20,
+    OpsNotSet = 21,
+}
+
+const STORAGE_KEY_PK: Symbol = symbol_short!("pk");
+const STORAGE_KEY_RP_ID_HASH: Symbol = symbol_short!("rpid");
+const STORAGE_KEY_REQUIRE_UV: Symbol = symbol_short!("uv");
+const STORAGE_KEY_ORIGINS: Symbol = symbol_short!("origins");
+const STORAGE_KEY_CROSS_ORIGIN: Symbol = symbol_short!("xorigin");
+const STORAGE_KEY_SIGN_COUNT: Symbol = symbol_short!("count");
+const STORAGE_KEY_VERSION: Symbol = symbol_short!("version");
+const STORAGE_KEY_CREDENTIAL_ID: Symbol = symbol_short!("credid");
+const STORAGE_KEY_OPS: Symbol = symbol_short!("ops");
+const STORAGE_KEY_LOCKED: Symbol = symbol_short!("locked");
+
+/// Versión del esquema de almacenamiento que escribe este código. Las
+/// cuentas creadas antes de guardar la versión se consideran versión 1.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Único tipo de client_data_json aceptado para autorizar llamadas
+const CLIENT_DATA_TYPE_GET: &str = "webauthn.get";
+/// Tipo de client_data_json de un registro
+const CLIENT_DATA_TYPE_CREATE: &str = "webauthn.create";
+
+/// Tamaño máximo de client_data_json. Los navegadores generan menos de 300
+/// bytes, pero algunos autenticadores añaden campos (orígenes de apps
+/// Android, `other_keys_can_be_added_here`, extensiones)
+const MAX_CLIENT_DATA_JSON_LEN: u32 = 2048;
+
+/// Tamaño máximo del attestationObject de un registro
+const MAX_ATTESTATION_OBJECT_LEN: u32 = 2048;
+/// Algoritmo COSE ES256 (ECDSA P-256 con SHA-256)
+const COSE_ALG_ES256: i64 = -7;
+
+#[contractimpl]
+impl WebAuthnAccount {
+    /// Extiende el TTL del contrato
+    pub fn extend_ttl(env: Env) {
+        let max_ttl = env.storage().max_ttl();
+        let contract_address = env.current_contract_address();
+
+        env.storage().instance().extend_ttl(max_ttl, max_ttl);
+        env.deployer()
+            .extend_ttl(contract_address.clone(), max_ttl, max_ttl);
+        env.deployer()
+            .extend_ttl_for_code(contract_address.clone(), max_ttl, max_ttl);
+        env.deployer()
+            .extend_ttl_for_contract_instance(contract_address.clone(), max_ttl, max_ttl);
+    }
+
+    /// Inicializa el contrato con una clave pública secp256r1, el hash
+    /// SHA-256 del identificador del relying party (rpId) y los orígenes
+    /// (web y apps móviles) desde los que se aceptan aserciones.
+    ///
+    /// La clave puede venir comprimida (33 bytes), sin comprimir (65 bytes),
+    /// como coordenadas X || Y (64 bytes) o como el COSE_Key del registro
+    /// (`attestedCredentialData`); se valida que sea un punto de la curva
+    /// P-256 y se guarda siempre sin comprimir.
+    pub fn init(
+        env: Env,
+        pk: Bytes,
+        rp_id_hash: BytesN<32>,
+        origins: Vec<String>,
+    ) -> Result<(), Error> {
+        if env.storage().instance().has(&STORAGE_KEY_PK) {
+            return Err(Error::AlreadyInited);
+        }
+        let pk = public_key::to_uncompressed(&pk).ok_or(Error::Secp256r1PublicKeyParse)?;
+
+        Self::store_account(&env, BytesN::from_array(&env, &pk), rp_id_hash, origins);
+
+        Ok(())
+    }
+
+    /// Inicializa la cuenta a partir de la respuesta de un registro WebAuthn
+    /// (`navigator.credentials.create`), guardando la clave y el credential
+    /// id solo si el attestationObject es válido:
+    ///
+    /// - client_data_json de tipo `webauthn.create` desde un origen permitido
+    /// - authData con el rpIdHash esperado, el flag UP y la credencial creada
+    /// - formato "packed" con autoatestación: la firma (DER) sobre
+    ///   `authData || sha256(client_data_json)` debe ser de la propia credencial
+    /// - formato "none": solo si `allow_none` lo permite
+    ///
+    /// La atestación con certificados (x5c) no está soportada. El challenge
+    /// del registro no se comprueba: lo que se prueba es que la clave
+    /// pertenece a un autenticador que firmó para este rpId y origen.
+    pub fn init_with_attestation(
+        env: Env,
+        attestation_object: Bytes,
+        client_data_json: Bytes,
+        rp_id_hash: BytesN<32>,
+        origins: Vec<String>,
+        allow_none: bool,
+    ) -> Result<(), Error> {
+        if env.storage().instance().has(&STORAGE_KEY_PK) {
+            return Err(Error::AlreadyInited);
+        }
+
+        // El registro debe venir de uno de los orígenes permitidos y no de
+        // un iframe de otro origen
+        let mut client_data_buffer = [0u8; MAX_CLIENT_DATA_JSON_LEN as usize];
+        let client_data = ClientDataJson::parse(&client_data_json, &mut client_data_buffer)?;
+        if client_data.type_ != CLIENT_DATA_TYPE_CREATE {
+            return Err(Error::ClientDataJsonTypeIncorrect);
+        }
+        Self::check_origin(&env, &origins, client_data.origin)?;
+        if client_data.cross_origin == Some(true) || client_data.top_origin.is_some() {
+            return Err(Error::CrossOriginNotAllowed);
+        }
+
+        // attestationObject = {"fmt": tstr, "attStmt": map, "authData": bstr}
+        if attestation_object.len() > MAX_ATTESTATION_OBJECT_LEN {
+            return Err(Error::AttestationParse);
+        }
+        let mut buffer = [0u8; MAX_ATTESTATION_OBJECT_LEN as usize];
+        let buffer = &mut buffer[..attestation_object.len() as usize];
+        attestation_object.copy_into_slice(buffer);
+        let attestation = Attestation::decode(buffer).ok_or(Error::AttestationParse)?;
+
+        // authData debe ser para este rpId, con el usuario presente y con la
+        // credencial recién creada
+        let auth_data = Bytes::from_slice(&env, attestation.auth_data);
+        let authenticator_data =
+            authenticator_data::parse(&env, &auth_data).ok_or(Error::AuthenticatorDataParse)?;
+        if authenticator_data.rp_id_hash != rp_id_hash {
+            return Err(Error::RpIdHashMismatch);
+        }
+        if !authenticator_data.user_present() {
+            return Err(Error::UserNotPresent);
+        }
+        let credential = authenticator_data
+            .attested_credential_data
+            .ok_or(Error::AttestationParse)?;
+        let pk = public_key::to_uncompressed(&Bytes::from_array(&env, &credential.public_key))
+            .ok_or(Error::Secp256r1PublicKeyParse)?;
+        let pk = BytesN::from_array(&env, &pk);
+
+        match attestation.fmt {
+            "none" => {
+                if !allow_none {
+                    return Err(Error::AttestationNoneNotAllowed);
+                }
+                if attestation.att_stmt != [0xa0] {
+                    return Err(Error::AttestationParse);
+                }
+            }
+            "packed" => {
+                let signature = PackedStatement::decode(attestation.att_stmt)?;
+
+                let mut payload = auth_data.clone();
+                payload.extend_from_array(&env.crypto().sha256(&client_data_json).to_array());
+                let payload_hash = env.crypto().sha256(&payload);
+
+                // Si la firma no corresponde a la clave de la credencial, el
+                // host aborta la ejecución y el registro falla
+                env.crypto().secp256r1_verify(
+                    &pk,
+                    &payload_hash,
+                    &BytesN::from_array(&env, &signature),
+                );
+            }
+            _ => return Err(Error::AttestationFormatUnsupported),
+        }
+
+        Self::store_account(&env, pk.clone(), rp_id_hash, origins);
+        env.storage()
+            .instance()
+            .set(&STORAGE_KEY_CREDENTIAL_ID, &credential.credential_id);
+        if authenticator_data.sign_count != 0 {
+            env.storage().instance().set(
+                &(STORAGE_KEY_SIGN_COUNT, pk),
+                &authenticator_data.sign_count,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Obtiene el credential id guardado en el registro (solo para cuentas
+    /// inicializadas con `init_with_attestation`)
+    pub fn get_credential_id(env: Env) -> Option<Bytes> {
+        env.storage().instance().get(&STORAGE_KEY_CREDENTIAL_ID)
+    }
+
+    /// Obtiene la versión del esquema de almacenamiento de la cuenta
+    pub fn get_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&STORAGE_KEY_VERSION)
+            .unwrap_or(1)
+    }
+
+    /// Reemplaza el código de la cuenta por el WASM (ya subido) con el hash
+    /// dado. Requiere la autorización de la propia cuenta. El nuevo código
+    /// entra en vigor al terminar la invocación; después hay que llamar a
+    /// `migrate` para convertir el almacenamiento.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        Self::get_public_key(env.clone())?;
+        env.current_contract_address().require_auth();
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        events::account_upgraded(&env, &new_wasm_hash);
+
+        Ok(())
+    }
+
+    /// Convierte el almacenamiento de una versión anterior del esquema a
+    /// `SCHEMA_VERSION` y devuelve la versión resultante. Solo reescribe el
+    /// estado existente, así que cualquiera puede llamarla; en una cuenta al
+    /// día no hace nada.
+    pub fn migrate(env: Env) -> Result<u32, Error> {
+        Self::get_public_key(env.clone())?;
+
+        let version = Self::get_version(env.clone());
+        if version > SCHEMA_VERSION {
+            return Err(Error::UnsupportedVersion);
+        }
+
+        // Cada cambio de esquema añade aquí su conversión desde `version`
+
+        if version < SCHEMA_VERSION || !env.storage().instance().has(&STORAGE_KEY_VERSION) {
+            env.storage()
+                .instance()
+                .set(&STORAGE_KEY_VERSION, &SCHEMA_VERSION);
+            events::account_migrated(&env, version, SCHEMA_VERSION);
+        }
+
+        Ok(SCHEMA_VERSION)
+    }
+
+    /// Obtiene la clave pública almacenada
+    pub fn get_public_key(env: Env) -> Result<BytesN<65>, Error> {
+        env.storage()
+            .instance()
+            .get(&STORAGE_KEY_PK)
+            .ok_or(Error::NotInited)
+    }
+
+    /// Exige (o deja de exigir) el flag UV (verificación del usuario) en
+    /// cada aserción. Requiere la autorización de la propia cuenta.
+    pub fn set_require_uv(env: Env, required: bool) -> Result<(), Error> {
+        if !env.storage().instance().has(&STORAGE_KEY_PK) {
+            return Err(Error::NotInited);
+        }
+        env.current_contract_address().require_auth();
+
+        env.storage()
+            .instance()
+            .set(&STORAGE_KEY_REQUIRE_UV, &required);
+        events::config_changed(&env, STORAGE_KEY_REQUIRE_UV, required);
+
+        Ok(())
+    }
+
+    /// Obtiene el último contador de firmas visto para la credencial
+    pub fn get_sign_count(env: Env) -> Result<u32, Error> {
+        let pk = Self::get_public_key(env.clone())?;
+        Ok(env
+            .storage()
+            .instance()
+            .get(&(STORAGE_KEY_SIGN_COUNT, pk))
+            .unwrap_or(0))
+    }
+
+    /// Obtiene la lista de orígenes permitidos
+    pub fn get_origins(env: Env) -> Vec<String> {
+        env.storage()
+            .instance()
+            .get(&STORAGE_KEY_ORIGINS)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Añade un origen a la lista de permitidos. Requiere la autorización
+    /// de la propia cuenta.
+    pub fn add_origin(env: Env, origin: String) -> Result<(), Error> {
+        let mut origins = Self::require_origins(&env)?;
+        env.current_contract_address().require_auth();
+
+        if !origins.contains(&origin) {
+            origins.push_back(origin.clone());
+            env.storage().instance().set(&STORAGE_KEY_ORIGINS, &origins);
+            events::origin_added(&env, &origin);
+        }
+
+        Ok(())
+    }
+
+    /// Elimina un origen de la lista de permitidos. Requiere la autorización
+    /// de la propia cuenta.
+    pub fn remove_origin(env: Env, origin: String) -> Result<(), Error> {
+        let mut origins = Self::require_origins(&env)?;
+        env.current_contract_address().require_auth();
+
+        if let Some(index) = origins.first_index_of(&origin) {
+            origins.remove(index);
+            env.storage().instance().set(&STORAGE_KEY_ORIGINS, &origins);
+            events::origin_removed(&env, &origin);
+        }
+
+        Ok(())
+    }
+
+    /// Permite (o rechaza) aserciones hechas dentro de un iframe de otro
+    /// origen (`crossOrigin`/`topOrigin`). Requiere la autorización de la
+    /// propia cuenta.
+    pub fn set_allow_cross_origin(env: Env, allowed: bool) -> Result<(), Error> {
+        Self::require_origins(&env)?;
+        env.current_contract_address().require_auth();
+
+        env.storage()
+            .instance()
+            .set(&STORAGE_KEY_CROSS_ORIGIN, &allowed);
+        events::config_changed(&env, STORAGE_KEY_CROSS_ORIGIN, allowed);
+
+        Ok(())
+    }
+
+    /// Designa la cuenta del equipo de operaciones, que puede bloquear la
+    /// cuenta con `lock` (o la quita con `None`). Requiere la autorización
+    /// de la propia cuenta, así que no se puede cambiar mientras está
+    /// bloqueada.
+    pub fn set_ops(env: Env, ops: Option<Address>) -> Result<(), Error> {
+        Self::get_public_key(env.clone())?;
+        env.current_contract_address().require_auth();
+
+        match &ops {
+            Some(ops) => env.storage().instance().set(&STORAGE_KEY_OPS, ops),
+            None => env.storage().instance().remove(&STORAGE_KEY_OPS),
+        }
+        events::ops_changed(&env, ops);
+
+        Ok(())
+    }
+
+    /// Obtiene la cuenta del equipo de operaciones (si hay)
+    pub fn get_ops(env: Env) -> Option<Address> {
+        env.storage().instance().get(&STORAGE_KEY_OPS)
+    }
+
+    /// Bloquea la cuenta: `__check_auth` rechaza toda aserción con
+    /// `AccountLocked` hasta que se llame a `unlock`. Pensado para cuando una
+    /// autorización falla con `SignCountNotIncreased`, que indica una posible
+    /// credencial clonada: el error no deja rastro en la cuenta, el bloqueo
+    /// sí. Requiere la autorización de la cuenta de operaciones.
+    pub fn lock(env: Env) -> Result<(), Error> {
+        let ops = Self::get_ops(env.clone()).ok_or(Error::OpsNotSet)?;
+        ops.require_auth();
+
+        env.storage().instance().set(&STORAGE_KEY_LOCKED, &true);
+        events::account_locked(&env, &ops);
+
+        Ok(())
+    }
+
+    /// Desbloquea la cuenta. Requiere la autorización de la cuenta de
+    /// operaciones: la passkey no puede, porque podría ser la clonada.
+    pub fn unlock(env: Env) -> Result<(), Error> {
+        let ops = Self::get_ops(env.clone()).ok_or(Error::OpsNotSet)?;
+        ops.require_auth();
+
+        env.storage().instance().remove(&STORAGE_KEY_LOCKED);
+        events::account_unlocked(&env, &ops);
+
+        Ok(())
+    }
+
+    /// Indica si la cuenta está bloqueada
+    pub fn is_locked(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&STORAGE_KEY_LOCKED)
+            .unwrap_or(false)
+    }
+}
+
+// Funciones auxiliares
+impl WebAuthnAccount {
+    /// Guarda la clave y la configuración de una cuenta nueva
+    fn store_account(env: &Env, pk: BytesN<65>, rp_id_hash: BytesN<32>, origins: Vec<String>) {
+        env.storage().instance().set(&STORAGE_KEY_PK, &pk);
+        env.storage()
+            .instance()
+            .set(&STORAGE_KEY_RP_ID_HASH, &rp_id_hash);
+        env.storage().instance().set(&STORAGE_KEY_ORIGINS, &origins);
+        env.storage()
+            .instance()
+            .set(&STORAGE_KEY_VERSION, &SCHEMA_VERSION);
+        events::account_initialized(env, &pk);
+
+        Self::extend_ttl(env.clone());
+    }
+
+    /// Obtiene la lista de orígenes, fallando si la cuenta no está inicializada
+    fn require_origins(env: &Env) -> Result<Vec<String>, Error> {
+        env.storage()
+            .instance()
+            .get(&STORAGE_KEY_ORIGINS)
+            .ok_or(Error::NotInited)
+    }
+
+    /// Verifica que `origin` esté en la lista de permitidos
+    fn check_origin(env: &Env, origins: &Vec<String>, origin: &str) -> Result<(), Error> {
+        if !origins.contains(String::from_str(env, origin)) {
+            return Err(Error::OriginNotAllowed);
+        }
+        Ok(())
+    }
+}
+
+/// Estructura de la firma WebAuthn
+#[contracttype]
+#[derive(Clone)]
+pub struct Signature {
+    pub authenticator_data: Bytes,
+    pub client_data_json: Bytes,
+    /// Firma ES256 en DER (como la devuelve el navegador) o como r || s (64 bytes)
+    pub signature: Bytes,
+}
+
+/// attestationObject de un registro, sin interpretar attStmt
+struct Attestation<'a> {
+    fmt: &'a str,
+    /// attStmt tal cual viene codificado en CBOR
+    att_stmt: &'a [u8],
+    auth_data: &'a [u8],
+}
+
+impl<'a> Attestation<'a> {
+    fn decode(data: &'a [u8]) -> Option<Self> {
+        let mut decoder = cbor::Decoder::new(data);
+        let mut fmt = None;
+        let mut att_stmt = None;
+        let mut auth_data = None;
+
+        for _ in 0..decoder.map()? {
+            let seen = match decoder.text()? {
+                "fmt" => fmt.replace(decoder.text()?).is_some(),
+                "attStmt" => att_stmt.replace(decoder.raw()?).is_some(),
+                "authData" => auth_data.replace(decoder.bytes()?).is_some(),
+                _ => {
+                    decoder.skip()?;
+                    false
+                }
+            };
+            if seen {
+                return None;
+            }
+        }
+        if !decoder.is_empty() {
+            return None;
+        }
+
+        Some(Attestation {
+            fmt: fmt?,
+            att_stmt: att_stmt?,
+            auth_data: auth_data?,
+        })
+    }
+}
+
+/// attStmt del formato "packed": `{"alg": int, "sig": bstr, "x5c"?: [bstr]}`
+struct PackedStatement;
+
+impl PackedStatement {
+    /// Devuelve la firma de autoatestación como r || s con s en forma baja
+    fn decode(att_stmt: &[u8]) -> Result<[u8; 64], Error> {
+        let mut decoder = cbor::Decoder::new(att_stmt);
+        let mut alg = None;
+        let mut sig = None;
+
+        for _ in 0..decoder.map().ok_or(Error::AttestationParse)? {
+            match decoder.text().ok_or(Error::AttestationParse)? {
+                "alg" => alg = Some(decoder.int().ok_or(Error::AttestationParse)?),
+                "sig" => sig = Some(decoder.bytes().ok_or(Error::AttestationParse)?),
+                // Atestación con certificados (o ECDAA): no soportada
+                "x5c" | "ecdaaKeyId" => return Err(Error::AttestationFormatUnsupported),
+                _ => decoder.skip().ok_or(Error::AttestationParse)?,
+            }
+        }
+        if !decoder.is_empty() {
+            return Err(Error::AttestationParse);
+        }
+
+        // En la autoatestación el algoritmo es el de la credencial
+        if alg.ok_or(Error::AttestationParse)? != COSE_ALG_ES256 {
+            return Err(Error::AttestationFormatUnsupported);
+        }
+        let mut signature = ecdsa::from_der(sig.ok_or(Error::AttestationParse)?)
+            .ok_or(Error::Secp256r1SignatureParse)?;
+        if !ecdsa::is_valid(&signature) {
+            return Err(Error::Secp256r1SignatureParse);
+        }
+        ecdsa::to_low_s(&mut signature);
+
+        Ok(signature)
+    }
+}
+
+/// Estructura para parsear client_data_json
+#[derive(serde::Deserialize)]
+struct ClientDataJson<'a> {
+    #[serde(rename = "type")]
+    type_: &'a str,
+    challenge: &'a str,
+    origin: &'a str,
+    #[serde(rename = "crossOrigin")]
+    cross_origin: Option<bool>,
+    #[serde(rename = "topOrigin")]
+    top_origin: Option<&'a str>,
+}
+
+impl<'a> ClientDataJson<'a> {
+    /// Copia client_data_json en `buffer` y lo parsea. Los campos
+    /// desconocidos se ignoran; si supera `MAX_CLIENT_DATA_JSON_LEN` se
+    /// rechaza con `JsonParseError`.
+    fn parse(
+        client_data_json: &Bytes,
+        buffer: &'a mut [u8; MAX_CLIENT_DATA_JSON_LEN as usize],
+    ) -> Result<Self, Error> {
+        if client_data_json.len() > MAX_CLIENT_DATA_JSON_LEN {
+            return Err(Error::JsonParseError);
+        }
+        let buffer = &mut buffer[..client_data_json.len() as usize];
+        client_data_json.copy_into_slice(buffer);
+
+        let (client_data, _) =
+            serde_json_core::de::from_slice(buffer).map_err(|_| Error::JsonParseError)?;
+        Ok(client_data)
+    }
+}
+
+#[contractimpl]
+impl CustomAccountInterface for WebAuthnAccount {
+    type Error = Error;
+    type Signature = Signature;
+
+    #[allow(non_snake_case)]
+    fn __check_auth(
+        env: Env,
+        signature_payload: Hash<32>,
+        signature: Signature,
+        _auth_contexts: Vec<Context>,
+    ) -> Result<(), Error> {
+        // Obtener la clave pública almacenada
+        let pk: BytesN<65> = env
+            .storage()
+            .instance()
+            .get(&STORAGE_KEY_PK)
+            .ok_or(Error::NotInited)?;
+
+        // La clave debe estar en formato SEC-1 sin comprimir (0x04 || X || Y)
+        if pk.get(0) != Some(0x04) {
+            return Err(Error::Secp256r1PublicKeyParse);
+        }
+
+        // Operaciones bloqueó la cuenta por una posible credencial clonada
+        if Self::is_locked(env.clone()) {
+            return Err(Error::AccountLocked);
+        }
+
+        // r y s deben ser escalares no nulos menores que el orden de la
+        // curva. El host solo verifica firmas con s en forma baja (s <= n/2),
+        // así que una s alta se sustituye por n - s, que es equivalente.
+        let signature_bytes =
+            ecdsa::parse(&signature.signature).ok_or(Error::Secp256r1SignatureParse)?;
+
+        // Parsear el client_data_json para extraer el challenge
+        let mut client_data_buffer = [0u8; MAX_CLIENT_DATA_JSON_LEN as usize];
+        let client_data =
+            ClientDataJson::parse(&signature.client_data_json, &mut client_data_buffer)?;
+
+        // Solo se aceptan aserciones (no registros ni otros tipos)
+        if client_data.type_ != CLIENT_DATA_TYPE_GET {
+            return Err(Error::ClientDataJsonTypeIncorrect);
+        }
+
+        // El origen debe estar en la lista de permitidos; las aserciones
+        // hechas desde un iframe de otro origen solo se aceptan si la cuenta
+        // lo permite y el origen superior también está en la lista
+        let origins = Self::require_origins(&env)?;
+        Self::check_origin(&env, &origins, client_data.origin)?;
+        let allow_cross_origin: bool = env
+            .storage()
+            .instance()
+            .get(&STORAGE_KEY_CROSS_ORIGIN)
+            .unwrap_or(false);
+        if client_data.cross_origin == Some(true) || client_data.top_origin.is_some() {
+            if !allow_cross_origin {
+                return Err(Error::CrossOriginNotAllowed);
+            }
+            if let Some(top_origin) = client_data.top_origin {
+                Self::check_origin(&env, &origins, top_origin)?;
+            }
+        }
+
+        // El challenge es el signature_payload codificado en base64url
+        let challenge = Bytes::from_slice(&env, client_data.challenge.as_bytes());
+        let challenge = base64_url::decode(&env, &challenge)
+            .map_err(|_| Error::ClientDataJsonChallengeIncorrect)?;
+        if challenge != Bytes::from_array(&env, &signature_payload.to_array()) {
+            return Err(Error::ClientDataJsonChallengeIncorrect);
+        }
+
+        // Interpretar authenticator_data: rpIdHash y flags UP/UV
+        let authenticator_data = authenticator_data::parse(&env, &signature.authenticator_data)
+            .ok_or(Error::AuthenticatorDataParse)?;
+        let rp_id_hash: BytesN<32> = env
+            .storage()
+            .instance()
+            .get(&STORAGE_KEY_RP_ID_HASH)
+            .ok_or(Error::NotInited)?;
+        if authenticator_data.rp_id_hash != rp_id_hash {
+            return Err(Error::RpIdHashMismatch);
+        }
+        if !authenticator_data.user_present() {
+            return Err(Error::UserNotPresent);
+        }
+        let require_uv: bool = env
+            .storage()
+            .instance()
+            .get(&STORAGE_KEY_REQUIRE_UV)
+            .unwrap_or(false);
+        if require_uv && !authenticator_data.user_verified() {
+            return Err(Error::UserNotVerified);
+        }
+
+        // Construir el payload para verificación
+        let mut payload = Bytes::new(&env);
+        payload.append(&signature.authenticator_data);
+        payload.extend_from_array(&env.crypto().sha256(&signature.client_data_json).to_array());
+        let payload_hash = env.crypto().sha256(&payload);
+
+        // Verificar la firma secp256r1. Si no corresponde a la clave
+        // almacenada, el host aborta la ejecución y la autorización falla.
+        env.crypto().secp256r1_verify(
+            &pk,
+            &payload_hash,
+            &BytesN::from_array(&env, &signature_bytes),
+        );
+
+        // El contador de firmas debe aumentar en cada aserción. Si no lo hace
+        // (y el autenticador soporta contadores) la credencial puede estar
+        // clonada y la aserción se rechaza. Una autorización fallida revierte
+        // todos sus cambios y eventos, así que la señal es el error
+        // `SignCountNotIncreased` en el resultado de la transacción; el
+        // equipo de operaciones lo vigila y bloquea la cuenta con `lock`.
+        let sign_count_key = (STORAGE_KEY_SIGN_COUNT, pk.clone());
+        let stored_count: u32 = env.storage().instance().get(&sign_count_key).unwrap_or(0);
+        let sign_count = authenticator_data.sign_count;
+        if sign_count != 0 || stored_count != 0 {
+            if sign_count <= stored_count {
+                return Err(Error::SignCountNotIncreased);
+            }
+            env.storage().instance().set(&sign_count_key, &sign_count);
+        }
+
+        events::auth_used(&env, &pk, sign_count);
+
+        Ok(())
+    }
+}
+
+// Eventos
+//
+// Todos los eventos llevan dos topics: el sujeto (`account`, `origin`,
+// `config`, `auth`) y lo que le ocurrió. Los topics son estables para que
+// los indexadores puedan reconstruir el historial de la cuenta.
+mod events {
+    use soroban_sdk::{symbol_short, Address, BytesN, Env, String, Symbol};
+
+    const ACCOUNT: Symbol = symbol_short!("account");
+    const ORIGIN: Symbol = symbol_short!("origin");
+    const CONFIG: Symbol = symbol_short!("config");
+    const AUTH: Symbol = symbol_short!("auth");
+
+    /// `(account, init)` - datos: clave pública
+    pub fn account_initialized(env: &Env, pk: &BytesN<65>) {
+        env.events()
+            .publish((ACCOUNT, symbol_short!("init")), pk.clone());
+    }
+
+    /// `(account, upgraded)` - datos: hash del nuevo WASM
+    pub fn account_upgraded(env: &Env, wasm_hash: &BytesN<32>) {
+        env.events()
+            .publish((ACCOUNT, symbol_short!("upgraded")), wasm_hash.clone());
+    }
+
+    /// `(account, migrated)` - datos: (versión anterior, versión nueva)
+    pub fn account_migrated(env: &Env, from: u32, to: u32) {
+        env.events()
+            .publish((ACCOUNT, symbol_short!("migrated")), (from, to));
+    }
+
+    /// `(account, locked)` - datos: cuenta de operaciones
+    pub fn account_locked(env: &Env, ops: &Address) {
+        env.events()
+            .publish((ACCOUNT, symbol_short!("locked")), ops.clone());
+    }
+
+    /// `(account, unlocked)` - datos: cuenta de operaciones
+    pub fn account_unlocked(env: &Env, ops: &Address) {
+        env.events()
+            .publish((ACCOUNT, symbol_short!("unlocked")), ops.clone());
+    }
+
+    /// `(origin, added)` - datos: origen
+    pub fn origin_added(env: &Env, origin: &String) {
+        env.events()
+            .publish((ORIGIN, symbol_short!("added")), origin.clone());
+    }
+
+    /// `(origin, removed)` - datos: origen
+    pub fn origin_removed(env: &Env, origin: &String) {
+        env.events()
+            .publish((ORIGIN, symbol_short!("removed")), origin.clone());
+    }
+
+    /// `(config, <clave>)` - datos: nuevo valor (`uv`, `xorigin`)
+    pub fn config_changed(env: &Env, key: Symbol, value: bool) {
+        env.events().publish((CONFIG, key), value);
+    }
+
+    /// `(config, ops)` - datos: nueva cuenta de operaciones (o ninguna)
+    pub fn ops_changed(env: &Env, ops: Option<Address>) {
+        env.events().publish((CONFIG, symbol_short!("ops")), ops);
+    }
+
+    /// `(auth, used)` - datos: (clave pública, contador de firmas)
+    pub fn auth_used(env: &Env, pk: &BytesN<65>, sign_count: u32) {
+        env.events()
+            .publish((AUTH, symbol_short!("used")), (pk.clone(), sign_count));
+    }
+}
+
+#[cfg(test)]
+mod webauthn_test {
+    extern crate std;
+
+    use super::*;
+    use authenticator_data::{FLAG_UP, FLAG_UV};
+    use p256::ecdsa::{signature::hazmat::PrehashSigner, Signature as P256Signature, SigningKey};
+    use soroban_sdk::{
+        testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Events},
+        vec, Address, IntoVal, Val,
+    };
+    use std::{format, string::String as StdString};
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_slice(&[7u8; 32]).unwrap()
+    }
+
+    /// 32 bytes derivados de `seed`, en lugar de valores aleatorios para
+    /// que los snapshots de los tests sean iguales en cada ejecución
+    fn fixed_bytes(env: &Env, seed: u8) -> BytesN<32> {
+        env.crypto()
+            .sha256(&Bytes::from_array(env, &[seed]))
+            .to_bytes()
+    }
+
+    fn encode_base64_url(env: &Env, data: &[u8]) -> StdString {
+        let encoded = base64_url::encode(env, &Bytes::from_slice(env, data));
+        let mut out = std::vec![0u8; encoded.len() as usize];
+        encoded.copy_into_slice(&mut out);
+        StdString::from_utf8(out).unwrap()
+    }
+
+    fn rp_id_hash(env: &Env) -> BytesN<32> {
+        env.crypto()
+            .sha256(&Bytes::from_slice(env, b"ebas.example"))
+            .to_bytes()
+    }
+
+    fn authenticator_data(env: &Env, flags: u8, sign_count: u32) -> Bytes {
+        let mut data = Bytes::from_array(env, &rp_id_hash(env).to_array());
+        data.push_back(flags);
+        data.extend_from_array(&sign_count.to_be_bytes());
+        data
+    }
+
+    const ORIGIN: &str = "https://ebas.example";
+
+    fn client_data(
+        env: &Env,
+        challenge: &[u8],
+        type_: &str,
+        origin: &str,
+        extra: &str,
+    ) -> StdString {
+        format!(
+            r#"{{"type":"{}","challenge":"{}","origin":"{}"{}}}"#,
+            type_,
+            encode_base64_url(env, challenge),
+            origin,
+            extra
+        )
+    }
+
+    fn client_data_json(env: &Env, challenge: &[u8]) -> StdString {
+        client_data(
+            env,
+            challenge,
+            "webauthn.get",
+            ORIGIN,
+            r#","crossOrigin":false"#,
+        )
+    }
+
+    fn sign(
+        env: &Env,
+        key: &SigningKey,
+        authenticator_data: Bytes,
+        client_data_json: &str,
+    ) -> Signature {
+        let client_data_json = Bytes::from_slice(env, client_data_json.as_bytes());
+
+        let mut payload = authenticator_data.clone();
+        payload.extend_from_array(&env.crypto().sha256(&client_data_json).to_array());
+        let digest = env.crypto().sha256(&payload).to_array();
+
+        let signature: P256Signature = key.sign_prehash(&digest).unwrap();
+        let signature = signature.normalize_s().unwrap_or(signature);
+
+        Signature {
+            authenticator_data,
+            client_data_json,
+            signature: Bytes::from_slice(env, &signature.to_bytes()),
+        }
+    }
+
+    /// La firma r || s de una aserción como firma de p256
+    fn p256_signature(signature: &Signature) -> P256Signature {
+        let mut bytes = [0u8; 64];
+        signature.signature.copy_into_slice(&mut bytes);
+        P256Signature::from_slice(&bytes).unwrap()
+    }
+
+    /// Una aserción válida sobre `payload` de la passkey de la cuenta
+    fn assertion(env: &Env, payload: &BytesN<32>) -> Signature {
+        assertion_with_count(env, payload, 1)
+    }
+
+    fn assertion_with_count(env: &Env, payload: &BytesN<32>, sign_count: u32) -> Signature {
+        sign(
+            env,
+            &signing_key(),
+            authenticator_data(env, FLAG_UP | FLAG_UV, sign_count),
+            &client_data_json(env, &payload.to_array()),
+        )
+    }
+
+    fn setup(env: &Env) -> Address {
+        let contract_id = env.register_contract(None, WebAuthnAccount);
+        let client = WebAuthnAccountClient::new(env, &contract_id);
+        let point = signing_key().verifying_key().to_encoded_point(false);
+        client.init(
+            &Bytes::from_slice(env, point.as_bytes()),
+            &rp_id_hash(env),
+            &vec![env, String::from_str(env, ORIGIN)],
+        );
+        contract_id
+    }
+
+    fn check_auth(
+        env: &Env,
+        contract_id: &Address,
+        payload: &BytesN<32>,
+        signature: Signature,
+    ) -> Result<(), Result<Error, soroban_sdk::InvokeError>> {
+        env.try_invoke_contract_check_auth::<Error>(
+            contract_id,
+            payload,
+            signature.into_val(env),
+            &vec![env],
+        )
+    }
+
+    #[test]
+    fn test_check_auth_valid_assertion() {
+        let env = Env::default();
+        let contract_id = setup(&env);
+        let payload: BytesN<32> = fixed_bytes(&env, 1);
+
+        let signature = assertion(&env, &payload);
+        assert_eq!(check_auth(&env, &contract_id, &payload, signature), Ok(()));
+    }
+
+    #[test]
+    fn test_check_auth_challenge_mismatch() {
+        let env = Env::default();
+        let contract_id = setup(&env);
+        let payload: BytesN<32> = fixed_bytes(&env, 1);
+
+        let signature = assertion(&env, &BytesN::from_array(&env, &[1u8; 32]));
+        assert_eq!(
+            check_auth(&env, &contract_id, &payload, signature),
+            Err(Ok(Error::ClientDataJsonChallengeIncorrect))
+        );
+        // Codificación no canónica del payload: los 2 bits sobrantes del
+        // último carácter no son cero
+        let payload = BytesN::from_array(&env, &[0u8; 32]);
+        let mut challenge = encode_base64_url(&env, &payload.to_array());
+        assert!(challenge.ends_with('A'));
+        challenge.pop();
+        challenge.push('B');
+        let client_data_json = client_data(&env, &[], "webauthn.get", ORIGIN, "").replace(
+            r#""challenge":"""#,
+            &format!(r#""challenge":"{}""#, challenge),
+        );
+        let signature = sign(
+            &env,
+            &signing_key(),
+            authenticator_data(&env, FLAG_UP, 1),
+            &client_data_json,
+        );
+        assert_eq!(
+            check_auth(&env, &contract_id, &payload, signature),
+            Err(Ok(Error::ClientDataJsonChallengeIncorrect))
+        );
+    }
+
+    #[test]
+    fn test_check_auth_invalid_json() {
+        let env = Env::default();
+        let contract_id = setup(&env);
+        let payload: BytesN<32> = fixed_bytes(&env, 1);
+
+        let signature = sign(
+            &env,
+            &signing_key(),
+            authenticator_data(&env, FLAG_UP, 1),
+            "not json",
+        );
+        assert_eq!(
+            check_auth(&env, &contract_id, &payload, signature),
+            Err(Ok(Error::JsonParseError))
+        );
+    }
+
+    #[test]
+    fn test_check_auth_malformed_signature() {
+        let env = Env::default();
+        let contract_id = setup(&env);
+        let payload: BytesN<32> = fixed_bytes(&env, 1);
+
+        let valid = assertion(&env, &payload);
+        let der = p256_signature(&valid).to_der();
+
+        let mut truncated_der = der.as_bytes().to_vec();
+        truncated_der.pop();
+        let mut trailing_der = der.as_bytes().to_vec();
+        trailing_der.push(0);
+        for malformed in [
+            std::vec![0u8; 64],
+            std::vec![1u8; 63],
+            truncated_der,
+            trailing_der,
+            std::vec![0x30, 0x06, 0x02, 0x01, 0x00, 0x02, 0x01, 0x01],
+        ] {
+            let mut signature = valid.clone();
+            signature.signature = Bytes::from_slice(&env, &malformed);
+            assert_eq!(
+                check_auth(&env, &contract_id, &payload, signature),
+                Err(Ok(Error::Secp256r1SignatureParse))
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_auth_der_signature() {
+        let env = Env::default();
+        let contract_id = setup(&env);
+        let payload: BytesN<32> = fixed_bytes(&env, 1);
+
+        let mut signature = assertion(&env, &payload);
+        let der = p256_signature(&signature).to_der();
+        signature.signature = Bytes::from_slice(&env, der.as_bytes());
+        assert_eq!(check_auth(&env, &contract_id, &payload, signature), Ok(()));
+    }
+
+    #[test]
+    fn test_check_auth_high_s_signature() {
+        let env = Env::default();
+        let contract_id = setup(&env);
+        let payload: BytesN<32> = fixed_bytes(&env, 1);
+
+        // La s alta se normaliza a n - s, tanto en r || s como en DER
+        let valid = assertion_with_count(&env, &payload, 1);
+        let low_s = p256_signature(&valid);
+        let high_s = P256Signature::from_scalars(low_s.r(), -*low_s.s()).unwrap();
+
+        let mut signature = valid.clone();
+        signature.signature = Bytes::from_slice(&env, &high_s.to_bytes());
+        assert_eq!(check_auth(&env, &contract_id, &payload, signature), Ok(()));
+
+        let mut signature = assertion_with_count(&env, &payload, 2);
+        let low_s = p256_signature(&signature);
+        let high_s = P256Signature::from_scalars(low_s.r(), -*low_s.s()).unwrap();
+        signature.signature = Bytes::from_slice(&env, high_s.to_der().as_bytes());
+        assert_eq!(check_auth(&env, &contract_id, &payload, signature), Ok(()));
+    }
+
+    #[test]
+    fn test_check_auth_wrong_key() {
+        let env = Env::default();
+        let contract_id = setup(&env);
+        let payload: BytesN<32> = fixed_bytes(&env, 1);
+
+        let other_key = SigningKey::from_slice(&[9u8; 32]).unwrap();
+        let signature = sign(
+            &env,
+            &other_key,
+            authenticator_data(&env, FLAG_UP, 1),
+            &client_data_json(&env, &payload.to_array()),
+        );
+        assert!(matches!(
+            check_auth(&env, &contract_id, &payload, signature),
+            Err(Err(_))
+        ));
+    }
+
+    #[test]
+    fn test_check_auth_not_inited() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, WebAuthnAccount);
+        let payload: BytesN<32> = fixed_bytes(&env, 1);
+
+        let signature = assertion(&env, &payload);
+        assert_eq!(
+            check_auth(&env, &contract_id, &payload, signature),
+            Err(Ok(Error::NotInited))
+        );
+    }
+
+    #[test]
+    fn test_check_auth_malformed_authenticator_data() {
+        let env = Env::default();
+        let contract_id = setup(&env);
+        let payload: BytesN<32> = fixed_bytes(&env, 1);
+
+        // Truncado antes del contador de firmas
+        let truncated = authenticator_data(&env, FLAG_UP, 1).slice(..33);
+        let signature = sign(
+            &env,
+            &signing_key(),
+            truncated,
+            &client_data_json(&env, &payload.to_array()),
+        );
+        assert_eq!(
+            check_auth(&env, &contract_id, &payload, signature),
+            Err(Ok(Error::AuthenticatorDataParse))
+        );
+    }
+
+    #[test]
+    fn test_check_auth_rp_id_hash_mismatch() {
+        let env = Env::default();
+        let contract_id = setup(&env);
+        let payload: BytesN<32> = fixed_bytes(&env, 1);
+
+        let mut data = Bytes::from_array(&env, &[0xaa; 32]);
+        data.push_back(FLAG_UP);
+        data.extend_from_array(&1u32.to_be_bytes());
+        let signature = sign(
+            &env,
+            &signing_key(),
+            data,
+            &client_data_json(&env, &payload.to_array()),
+        );
+        assert_eq!(
+            check_auth(&env, &contract_id, &payload, signature),
+            Err(Ok(Error::RpIdHashMismatch))
+        );
+    }
+
+    #[test]
+    fn test_check_auth_user_presence_and_verification() {
+        let env = Env::default();
+        let contract_id = setup(&env);
+        let client = WebAuthnAccountClient::new(&env, &contract_id);
+        let payload: BytesN<32> = fixed_bytes(&env, 1);
+        let client_data_json = client_data_json(&env, &payload.to_array());
+
+        let signature = sign(
+            &env,
+            &signing_key(),
+            authenticator_data(&env, FLAG_UV, 1),
+            &client_data_json,
+        );
+        assert_eq!(
+            check_auth(&env, &contract_id, &payload, signature),
+            Err(Ok(Error::UserNotPresent))
+        );
+
+        // Basta con UP hasta que la cuenta exija la verificación del usuario
+        let present_only = authenticator_data(&env, FLAG_UP, 1);
+        let signature = sign(
+            &env,
+            &signing_key(),
+            present_only.clone(),
+            &client_data_json,
+        );
+        assert_eq!(check_auth(&env, &contract_id, &payload, signature), Ok(()));
+
+        env.mock_all_auths();
+        client.set_require_uv(&true);
+
+        let signature = sign(&env, &signing_key(), present_only, &client_data_json);
+        assert_eq!(
+            check_auth(&env, &contract_id, &payload, signature),
+            Err(Ok(Error::UserNotVerified))
+        );
+    }
+
+    #[test]
+    fn test_check_auth_client_data_type() {
+        let env = Env::default();
+        let contract_id = setup(&env);
+        let payload: BytesN<32> = fixed_bytes(&env, 1);
+
+        let signature = sign(
+            &env,
+            &signing_key(),
+            authenticator_data(&env, FLAG_UP, 1),
+            &client_data(&env, &payload.to_array(), "webauthn.create", ORIGIN, ""),
+        );
+        assert_eq!(
+            check_auth(&env, &contract_id, &payload, signature),
+            Err(Ok(Error::ClientDataJsonTypeIncorrect))
+        );
+    }
+
+    #[test]
+    fn test_check_auth_origin_allow_list() {
+        let env = Env::default();
+        let contract_id = setup(&env);
+        let client = WebAuthnAccountClient::new(&env, &contract_id);
+        let payload: BytesN<32> = fixed_bytes(&env, 1);
+        let android = "android:apk-key-hash:2fJm4Lx3Ccz3FKbLTLPNXGSYPCGsZZtQyUDJY5FkJwU";
+
+        let phishing = client_data(
+            &env,
+            &payload.to_array(),
+            "webauthn.get",
+            "https://ebas.example.evil",
+            "",
+        );
+        let signature = sign(
+            &env,
+            &signing_key(),
+            authenticator_data(&env, FLAG_UP, 1),
+            &phishing,
+        );
+        assert_eq!(
+            check_auth(&env, &contract_id, &payload, signature),
+            Err(Ok(Error::OriginNotAllowed))
+        );
+
+        let mobile = client_data(&env, &payload.to_array(), "webauthn.get", android, "");
+        let signature = sign(
+            &env,
+            &signing_key(),
+            authenticator_data(&env, FLAG_UP, 1),
+            &mobile,
+        );
+        assert_eq!(
+            check_auth(&env, &contract_id, &payload, signature.clone()),
+            Err(Ok(Error::OriginNotAllowed))
+        );
+
+        env.mock_all_auths();
+        client.add_origin(&String::from_str(&env, android));
+        assert_eq!(client.get_origins().len(), 2);
+        assert_eq!(
+            check_auth(&env, &contract_id, &payload, signature.clone()),
+            Ok(())
+        );
+
+        client.remove_origin(&String::from_str(&env, android));
+        assert_eq!(
+            check_auth(&env, &contract_id, &payload, signature),
+            Err(Ok(Error::OriginNotAllowed))
+        );
+    }
+
+    #[test]
+    fn test_check_auth_cross_origin() {
+        let env = Env::default();
+        let contract_id = setup(&env);
+        let client = WebAuthnAccountClient::new(&env, &contract_id);
+        let payload: BytesN<32> = fixed_bytes(&env, 1);
+
+        let embedded = client_data(
+            &env,
+            &payload.to_array(),
+            "webauthn.get",
+            ORIGIN,
+            r#","crossOrigin":true,"topOrigin":"https://partner.example""#,
+        );
+        let signature = sign(
+            &env,
+            &signing_key(),
+            authenticator_data(&env, FLAG_UP, 1),
+            &embedded,
+        );
+        assert_eq!(
+            check_auth(&env, &contract_id, &payload, signature.clone()),
+            Err(Ok(Error::CrossOriginNotAllowed))
+        );
+
+        // Una vez permitido, el origen superior también debe estar en la lista
+        env.mock_all_auths();
+        client.set_allow_cross_origin(&true);
+        assert_eq!(
+            check_auth(&env, &contract_id, &payload, signature.clone()),
+            Err(Ok(Error::OriginNotAllowed))
+        );
+
+        client.add_origin(&String::from_str(&env, "https://partner.example"));
+        assert_eq!(check_auth(&env, &contract_id, &payload, signature), Ok(()));
+    }
+
+    #[test]
+    fn test_check_auth_sign_count() {
+        let env = Env::default();
+        let contract_id = setup(&env);
+        let client = WebAuthnAccountClient::new(&env, &contract_id);
+        let payload: BytesN<32> = fixed_bytes(&env, 1);
+        let client_data_json = client_data_json(&env, &payload.to_array());
+
+        let signature = sign(
+            &env,
+            &signing_key(),
+            authenticator_data(&env, FLAG_UP, 5),
+            &client_data_json,
+        );
+        assert_eq!(
+            check_auth(&env, &contract_id, &payload, signature.clone()),
+            Ok(())
+        );
+        assert_eq!(client.get_sign_count(), 5);
+
+        // Repetir el mismo contador (o uno menor) parece un autenticador clonado
+        let events = env.events().all().len();
+        assert_eq!(
+            check_auth(&env, &contract_id, &payload, signature),
+            Err(Ok(Error::SignCountNotIncreased))
+        );
+        let signature = sign(
+            &env,
+            &signing_key(),
+            authenticator_data(&env, FLAG_UP, 3),
+            &client_data_json,
+        );
+        assert_eq!(
+            check_auth(&env, &contract_id, &payload, signature),
+            Err(Ok(Error::SignCountNotIncreased))
+        );
+        let signature = sign(
+            &env,
+            &signing_key(),
+            authenticator_data(&env, FLAG_UP, 0),
+            &client_data_json,
+        );
+        assert_eq!(
+            check_auth(&env, &contract_id, &payload, signature),
+            Err(Ok(Error::SignCountNotIncreased))
+        );
+        // Lo único que queda es el error: la autorización fallida no cambia
+        // el contador ni publica eventos
+        assert_eq!(client.get_sign_count(), 5);
+        assert_eq!(env.events().all().len(), events);
+
+        let signature = sign(
+            &env,
+            &signing_key(),
+            authenticator_data(&env, FLAG_UP, 6),
+            &client_data_json,
+        );
+        assert_eq!(check_auth(&env, &contract_id, &payload, signature), Ok(()));
+        assert_eq!(client.get_sign_count(), 6);
+    }
+
+    #[test]
+    fn test_ops_lock() {
+        let env = Env::default();
+        let contract_id = setup(&env);
+        let client = WebAuthnAccountClient::new(&env, &contract_id);
+        let payload: BytesN<32> = fixed_bytes(&env, 1);
+        let ops = Address::generate(&env);
+        let last_event = |topic: Symbol, data: Val| {
+            let events = env.events().all();
+            assert_eq!(
+                events.slice(events.len() - 1..),
+                vec![
+                    &env,
+                    (
+                        contract_id.clone(),
+                        (symbol_short!("account"), topic).into_val(&env),
+                        data,
+                    ),
+                ]
+            );
+        };
+
+        // Sin cuenta de operaciones nadie puede bloquearla
+        env.mock_all_auths();
+        assert_eq!(client.try_lock(), Err(Ok(Error::OpsNotSet)));
+        client.set_ops(&Some(ops.clone()));
+        assert_eq!(client.get_ops(), Some(ops.clone()));
+
+        // Tras un SignCountNotIncreased, operaciones bloquea la cuenta y
+        // ninguna aserción vale, aunque sea válida
+        assert_eq!(
+            check_auth(
+                &env,
+                &contract_id,
+                &payload,
+                assertion_with_count(&env, &payload, 5)
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            check_auth(
+                &env,
+                &contract_id,
+                &payload,
+                assertion_with_count(&env, &payload, 5)
+            ),
+            Err(Ok(Error::SignCountNotIncreased))
+        );
+        client.lock();
+        assert_eq!(
+            env.auths(),
+            std::vec![(
+                ops.clone(),
+                AuthorizedInvocation {
+                    function: AuthorizedFunction::Contract((
+                        contract_id.clone(),
+                        symbol_short!("lock"),
+                        vec![&env],
+                    )),
+                    sub_invocations: std::vec![],
+                }
+            )]
+        );
+        last_event(symbol_short!("locked"), ops.into_val(&env));
+        assert!(client.is_locked());
+        assert_eq!(
+            check_auth(
+                &env,
+                &contract_id,
+                &payload,
+                assertion_with_count(&env, &payload, 6)
+            ),
+            Err(Ok(Error::AccountLocked))
+        );
+
+        // Solo operaciones puede desbloquearla
+        client.unlock();
+        assert_eq!(env.auths()[0].0, ops);
+        last_event(symbol_short!("unlocked"), ops.into_val(&env));
+        assert!(!client.is_locked());
+        assert_eq!(
+            check_auth(
+                &env,
+                &contract_id,
+                &payload,
+                assertion_with_count(&env, &payload, 6)
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_check_auth_without_sign_count_support() {
+        let env = Env::default();
+        let contract_id = setup(&env);
+        let client = WebAuthnAccountClient::new(&env, &contract_id);
+        let payload: BytesN<32> = fixed_bytes(&env, 1);
+
+        // Los autenticadores que siempre devuelven cero (p. ej. passkeys
+        // sincronizadas) siguen funcionando
+        for _ in 0..2 {
+            let signature = assertion_with_count(&env, &payload, 0);
+            assert_eq!(check_auth(&env, &contract_id, &payload, signature), Ok(()));
+        }
+        assert_eq!(client.get_sign_count(), 0);
+    }
+
+    #[test]
+    fn test_upgrade_requires_account_auth() {
+        let env = Env::default();
+        let contract_id = setup(&env);
+        let client = WebAuthnAccountClient::new(&env, &contract_id);
+
+        assert!(client.try_upgrade(&fixed_bytes(&env, 1)).is_err());
+        assert_eq!(client.get_version(), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_migrate() {
+        let env = Env::default();
+        let contract_id = setup(&env);
+        let client = WebAuthnAccountClient::new(&env, &contract_id);
+
+        // Cuentas creadas antes de guardar la versión
+        env.as_contract(&contract_id, || {
+            env.storage().instance().remove(&STORAGE_KEY_VERSION);
+        });
+        assert_eq!(client.get_version(), 1);
+        assert_eq!(client.migrate(), SCHEMA_VERSION);
+        assert_eq!(client.get_version(), SCHEMA_VERSION);
+
+        env.as_contract(&contract_id, || {
+            env.storage()
+                .instance()
+                .set(&STORAGE_KEY_VERSION, &(SCHEMA_VERSION + 1));
+        });
+        assert_eq!(client.try_migrate(), Err(Ok(Error::UnsupportedVersion)));
+    }
+
+    #[test]
+    fn test_events() {
+        let env = Env::default();
+        let contract_id = setup(&env);
+        let pk = WebAuthnAccountClient::new(&env, &contract_id).get_public_key();
+        let payload: BytesN<32> = fixed_bytes(&env, 1);
+
+        let signature = assertion_with_count(&env, &payload, 3);
+        assert_eq!(check_auth(&env, &contract_id, &payload, signature), Ok(()));
+
+        let events = env.events().all();
+        assert_eq!(
+            events.slice(events.len() - 1..),
+            vec![
+                &env,
+                (
+                    contract_id,
+                    (symbol_short!("auth"), symbol_short!("used")).into_val(&env),
+                    (pk, 3u32).into_val(&env),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_init_normalizes_public_key() {
+        let env = Env::default();
+        let point = signing_key().verifying_key().to_encoded_point(false);
+        let uncompressed = BytesN::from_array(&env, point.as_bytes().try_into().unwrap());
+        let compressed = signing_key().verifying_key().to_encoded_point(true);
+
+        for pk in [
+            Bytes::from_slice(&env, compressed.as_bytes()),
+            Bytes::from_slice(&env, &point.as_bytes()[1..]),
+        ] {
+            let contract_id = env.register_contract(None, WebAuthnAccount);
+            let client = WebAuthnAccountClient::new(&env, &contract_id);
+            client.init(
+                &pk,
+                &rp_id_hash(&env),
+                &vec![&env, String::from_str(&env, ORIGIN)],
+            );
+            assert_eq!(client.get_public_key(), uncompressed);
+
+            let payload: BytesN<32> = fixed_bytes(&env, 1);
+            let signature = assertion(&env, &payload);
+            assert_eq!(check_auth(&env, &contract_id, &payload, signature), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_init_rejects_invalid_public_key() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, WebAuthnAccount);
+        let client = WebAuthnAccountClient::new(&env, &contract_id);
+        let origins = vec![&env, String::from_str(&env, ORIGIN)];
+
+        // Coordenadas que no están en la curva
+        let point = signing_key().verifying_key().to_encoded_point(false);
+        let mut pk = Bytes::from_slice(&env, point.as_bytes());
+        pk.set(64, pk.get(64).unwrap() ^ 1);
+        assert_eq!(
+            client.try_init(&pk, &rp_id_hash(&env), &origins),
+            Err(Ok(Error::Secp256r1PublicKeyParse))
+        );
+        assert_eq!(
+            client.try_init(
+                &Bytes::from_array(&env, &[4u8; 40]),
+                &rp_id_hash(&env),
+                &origins
+            ),
+            Err(Ok(Error::Secp256r1PublicKeyParse))
+        );
+        assert_eq!(client.try_get_public_key(), Err(Ok(Error::NotInited)));
+    }
+
+    #[test]
+    fn test_init_from_cose_key() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, WebAuthnAccount);
+        let client = WebAuthnAccountClient::new(&env, &contract_id);
+        let point = signing_key().verifying_key().to_encoded_point(false);
+
+        // {1: 2 (EC2), 3: -7 (ES256), -1: 1 (P-256), -2: x, -3: y}
+        let mut cose_key = Bytes::from_array(
+            &env,
+            &[0xa5, 0x01, 0x02, 0x03, 0x26, 0x20, 0x01, 0x21, 0x58, 0x20],
+        );
+        cose_key.extend_from_slice(&point.as_bytes()[1..33]);
+        cose_key.extend_from_array(&[0x22, 0x58, 0x20]);
+        cose_key.extend_from_slice(&point.as_bytes()[33..]);
+
+        client.init(
+            &cose_key,
+            &rp_id_hash(&env),
+            &vec![&env, String::from_str(&env, ORIGIN)],
+        );
+        assert_eq!(
+            client.get_public_key(),
+            BytesN::from_array(&env, point.as_bytes().try_into().unwrap())
+        );
+    }
+
+    const CREDENTIAL_ID: [u8; 16] = [0xc1; 16];
+
+    /// COSE_Key de la clave de prueba
+    fn cose_key(point: &[u8]) -> std::vec::Vec<u8> {
+        let mut key = std::vec![0xa5, 0x01, 0x02, 0x03, 0x26, 0x20, 0x01, 0x21, 0x58, 0x20];
+        key.extend_from_slice(&point[1..33]);
+        key.extend_from_slice(&[0x22, 0x58, 0x20]);
+        key.extend_from_slice(&point[33..]);
+        key
+    }
+
+    /// authData de un registro con la credencial de `key`
+    fn registration_data(env: &Env, key: &SigningKey, flags: u8, sign_count: u32) -> Bytes {
+        let point = key.verifying_key().to_encoded_point(false);
+        let mut data = authenticator_data(env, flags | authenticator_data::FLAG_AT, sign_count);
+        data.extend_from_array(&[0u8; 16]);
+        data.extend_from_array(&(CREDENTIAL_ID.len() as u16).to_be_bytes());
+        data.extend_from_array(&CREDENTIAL_ID);
+        data.extend_from_slice(&cose_key(point.as_bytes()));
+        data
+    }
+
+    fn cbor_text(text: &str) -> std::vec::Vec<u8> {
+        let mut item = std::vec![0x60 | text.len() as u8];
+        item.extend_from_slice(text.as_bytes());
+        item
+    }
+
+    fn cbor_bytes(bytes: &[u8]) -> std::vec::Vec<u8> {
+        let mut item = std::vec![0x59];
+        item.extend_from_slice(&(bytes.len() as u16).to_be_bytes());
+        item.extend_from_slice(bytes);
+        item
+    }
+
+    /// `{"fmt": fmt, "attStmt": att_stmt, "authData": auth_data}`
+    fn attestation_object(env: &Env, fmt: &str, att_stmt: &[u8], auth_data: &Bytes) -> Bytes {
+        let mut object = std::vec![0xa3];
+        object.extend(cbor_text("fmt"));
+        object.extend(cbor_text(fmt));
+        object.extend(cbor_text("attStmt"));
+        object.extend_from_slice(att_stmt);
+        object.extend(cbor_text("authData"));
+        let mut auth_data_buffer = std::vec![0u8; auth_data.len() as usize];
+        auth_data.copy_into_slice(&mut auth_data_buffer);
+        object.extend(cbor_bytes(&auth_data_buffer));
+        Bytes::from_slice(env, &object)
+    }
+
+    /// Firma de autoatestación sobre `auth_data || sha256(client_data_json)`
+    fn self_attestation(
+        env: &Env,
+        key: &SigningKey,
+        auth_data: &Bytes,
+        client_data_json: &str,
+    ) -> P256Signature {
+        let mut payload = auth_data.clone();
+        payload.extend_from_array(
+            &env.crypto()
+                .sha256(&Bytes::from_slice(env, client_data_json.as_bytes()))
+                .to_array(),
+        );
+        let digest = env.crypto().sha256(&payload).to_array();
+        let signature: P256Signature = key.sign_prehash(&digest).unwrap();
+        signature.normalize_s().unwrap_or(signature)
+    }
+
+    /// attStmt "packed": `{"alg": alg, "sig": der}`
+    fn packed_statement(alg: i8, der: &[u8]) -> std::vec::Vec<u8> {
+        let mut statement = std::vec![0xa2];
+        statement.extend(cbor_text("alg"));
+        statement.push(if alg < 0 {
+            0x20 | (-1 - alg) as u8
+        } else {
+            alg as u8
+        });
+        statement.extend(cbor_text("sig"));
+        statement.extend(cbor_bytes(der));
+        statement
+    }
+
+    fn registration_client_data(env: &Env) -> StdString {
+        client_data(
+            env,
+            b"registration",
+            "webauthn.create",
+            ORIGIN,
+            r#","crossOrigin":false"#,
+        )
+    }
+
+    fn register(
+        env: &Env,
+        attestation_object: &Bytes,
+        client_data_json: &str,
+        allow_none: bool,
+    ) -> (Address, Result<(), Result<Error, soroban_sdk::InvokeError>>) {
+        let contract_id = env.register_contract(None, WebAuthnAccount);
+        let client = WebAuthnAccountClient::new(env, &contract_id);
+        let result = client
+            .try_init_with_attestation(
+                attestation_object,
+                &Bytes::from_slice(env, client_data_json.as_bytes()),
+                &rp_id_hash(env),
+                &vec![env, String::from_str(env, ORIGIN)],
+                &allow_none,
+            )
+            .map(|_| ());
+        (contract_id, result)
+    }
+
+    #[test]
+    fn test_init_with_packed_self_attestation() {
+        let env = Env::default();
+        let key = signing_key();
+        let client_data_json = registration_client_data(&env);
+        let auth_data = registration_data(&env, &key, FLAG_UP | FLAG_UV, 5);
+        let signature = self_attestation(&env, &key, &auth_data, &client_data_json);
+        let high_s = P256Signature::from_scalars(signature.r(), -*signature.s()).unwrap();
+
+        // La firma DER se acepta tanto con s en forma baja como alta
+        for signature in [signature, high_s] {
+            let statement = packed_statement(-7, signature.to_der().as_bytes());
+            let object = attestation_object(&env, "packed", &statement, &auth_data);
+            let (contract_id, result) = register(&env, &object, &client_data_json, false);
+            assert_eq!(result, Ok(()));
+
+            let client = WebAuthnAccountClient::new(&env, &contract_id);
+            let point = key.verifying_key().to_encoded_point(false);
+            let pk = BytesN::from_array(&env, point.as_bytes().try_into().unwrap());
+            assert_eq!(client.get_public_key(), pk);
+            assert_eq!(
+                client.get_credential_id(),
+                Some(Bytes::from_array(&env, &CREDENTIAL_ID))
+            );
+
+            // El contador del registro se guarda: una aserción con un contador
+            // menor se detecta como clonada
+            let payload: BytesN<32> = fixed_bytes(&env, 1);
+            let signature = assertion_with_count(&env, &payload, 4);
+            assert_eq!(
+                check_auth(&env, &contract_id, &payload, signature),
+                Err(Ok(Error::SignCountNotIncreased))
+            );
+            let signature = assertion_with_count(&env, &payload, 6);
+            assert_eq!(check_auth(&env, &contract_id, &payload, signature), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_init_with_none_attestation() {
+        let env = Env::default();
+        let auth_data = registration_data(&env, &signing_key(), FLAG_UP, 0);
+        let object = attestation_object(&env, "none", &[0xa0], &auth_data);
+
+        let (_, result) = register(&env, &object, &registration_client_data(&env), false);
+        assert_eq!(result, Err(Ok(Error::AttestationNoneNotAllowed)));
+
+        let (contract_id, result) = register(&env, &object, &registration_client_data(&env), true);
+        assert_eq!(result, Ok(()));
+        let payload: BytesN<32> = fixed_bytes(&env, 1);
+        let signature = assertion(&env, &payload);
+        assert_eq!(check_auth(&env, &contract_id, &payload, signature), Ok(()));
+
+        // "none" no lleva attStmt
+        let statement = packed_statement(-7, &[0x30, 0x00]);
+        let object = attestation_object(&env, "none", &statement, &auth_data);
+        let (_, result) = register(&env, &object, &registration_client_data(&env), true);
+        assert_eq!(result, Err(Ok(Error::AttestationParse)));
+    }
+
+    #[test]
+    fn test_init_with_attestation_rejects_invalid_registration() {
+        let env = Env::default();
+        let key = signing_key();
+        let client_data_json = registration_client_data(&env);
+        let auth_data = registration_data(&env, &key, FLAG_UP, 0);
+        let signature = self_attestation(&env, &key, &auth_data, &client_data_json);
+        let der = signature.to_der();
+        let packed = attestation_object(
+            &env,
+            "packed",
+            &packed_statement(-7, der.as_bytes()),
+            &auth_data,
+        );
+
+        // client_data_json de una aserción o de otro origen
+        let assertion_data = client_data(&env, b"registration", "webauthn.get", ORIGIN, "");
+        assert_eq!(
+            register(&env, &packed, &assertion_data, false).1,
+            Err(Ok(Error::ClientDataJsonTypeIncorrect))
+        );
+        let other_origin = client_data(
+            &env,
+            b"registration",
+            "webauthn.create",
+            "https://evil.example",
+            "",
+        );
+        assert_eq!(
+            register(&env, &packed, &other_origin, false).1,
+            Err(Ok(Error::OriginNotAllowed))
+        );
+
+        // authData de otro rpId o sin credencial
+        let mut wrong_rp = auth_data.clone();
+        wrong_rp.set(0, wrong_rp.get(0).unwrap() ^ 1);
+        let object = attestation_object(
+            &env,
+            "packed",
+            &packed_statement(-7, der.as_bytes()),
+            &wrong_rp,
+        );
+        assert_eq!(
+            register(&env, &object, &client_data_json, false).1,
+            Err(Ok(Error::RpIdHashMismatch))
+        );
+        let no_credential = authenticator_data(&env, FLAG_UP, 0);
+        let object = attestation_object(&env, "none", &[0xa0], &no_credential);
+        assert_eq!(
+            register(&env, &object, &client_data_json, true).1,
+            Err(Ok(Error::AttestationParse))
+        );
+
+        // Formatos y algoritmos no soportados
+        let object = attestation_object(&env, "tpm", &[0xa0], &auth_data);
+        assert_eq!(
+            register(&env, &object, &client_data_json, true).1,
+            Err(Ok(Error::AttestationFormatUnsupported))
+        );
+        let object = attestation_object(
+            &env,
+            "packed",
+            &packed_statement(-8, der.as_bytes()),
+            &auth_data,
+        );
+        assert_eq!(
+            register(&env, &object, &client_data_json, false).1,
+            Err(Ok(Error::AttestationFormatUnsupported))
+        );
+        let mut with_x5c = packed_statement(-7, der.as_bytes());
+        with_x5c[0] = 0xa3;
+        with_x5c.extend(cbor_text("x5c"));
+        with_x5c.push(0x80);
+        let object = attestation_object(&env, "packed", &with_x5c, &auth_data);
+        assert_eq!(
+            register(&env, &object, &client_data_json, false).1,
+            Err(Ok(Error::AttestationFormatUnsupported))
+        );
+
+        // Firma que no es DER válido
+        let mut bad_der = der.as_bytes().to_vec();
+        bad_der.push(0);
+        let object =
+            attestation_object(&env, "packed", &packed_statement(-7, &bad_der), &auth_data);
+        assert_eq!(
+            register(&env, &object, &client_data_json, false).1,
+            Err(Ok(Error::Secp256r1SignatureParse))
+        );
+
+        // attestationObject que no es CBOR válido
+        assert_eq!(
+            register(
+                &env,
+                &Bytes::from_array(&env, &[0xa3, 0x63]),
+                &client_data_json,
+                true
+            )
+            .1,
+            Err(Ok(Error::AttestationParse))
+        );
+    }
+
+    #[test]
+    fn test_init_with_attestation_wrong_signer() {
+        let env = Env::default();
+        let key = signing_key();
+        let client_data_json = registration_client_data(&env);
+        let auth_data = registration_data(&env, &key, FLAG_UP, 0);
+
+        // Firmada por otra clave: el host aborta la verificación
+        let other = SigningKey::from_slice(&[9u8; 32]).unwrap();
+        let signature = self_attestation(&env, &other, &auth_data, &client_data_json);
+        let statement = packed_statement(-7, signature.to_der().as_bytes());
+        let object = attestation_object(&env, "packed", &statement, &auth_data);
+        assert!(matches!(
+            register(&env, &object, &client_data_json, false).1,
+            Err(Err(_))
+        ));
+
+        // Ya inicializada
+        let signature = self_attestation(&env, &key, &auth_data, &client_data_json);
+        let statement = packed_statement(-7, signature.to_der().as_bytes());
+        let object = attestation_object(&env, "packed", &statement, &auth_data);
+        let (contract_id, result) = register(&env, &object, &client_data_json, false);
+        assert_eq!(result, Ok(()));
+        let client = WebAuthnAccountClient::new(&env, &contract_id);
+        assert_eq!(
+            client.try_init_with_attestation(
+                &object,
+                &Bytes::from_slice(&env, client_data_json.as_bytes()),
+                &rp_id_hash(&env),
+                &vec![&env, String::from_str(&env, ORIGIN)],
+                &false,
+            ),
+            Err(Ok(Error::AlreadyInited))
+        );
+    }
+
+    /// Orígenes de una app Android: el hash de su certificado de firma
+    const ANDROID_ORIGIN: &str = "android:apk-key-hash:Xsj7Ih8cLtDWgVOBNdRrXT-p5gBcLYxDvEgVIBdqAkw";
+
+    fn check_client_data(
+        env: &Env,
+        contract_id: &Address,
+        client_data_json: &str,
+    ) -> Result<(), Result<Error, soroban_sdk::InvokeError>> {
+        let payload: BytesN<32> = fixed_bytes(env, 1);
+        let client_data_json =
+            client_data_json.replace("{challenge}", &encode_base64_url(env, &payload.to_array()));
+        let signature = sign(
+            env,
+            &signing_key(),
+            authenticator_data(env, FLAG_UP | FLAG_UV, 0),
+            &client_data_json,
+        );
+        check_auth(env, contract_id, &payload, signature)
+    }
+
+    #[test]
+    fn test_check_auth_real_world_client_data() {
+        let env = Env::default();
+        let contract_id = setup(&env);
+        let client = WebAuthnAccountClient::new(&env, &contract_id);
+        env.mock_all_auths();
+        client.add_origin(&String::from_str(&env, ANDROID_ORIGIN));
+
+        // Chrome añade una clave para que no se compare contra una plantilla
+        let chrome = r#"{"type":"webauthn.get","challenge":"{challenge}","origin":"https://ebas.example","crossOrigin":false,"other_keys_can_be_added_here":"do not compare clientDataJSON against a template. See https://goo.gl/yabPex"}"#;
+        // Safari no incluye crossOrigin
+        let safari =
+            r#"{"type":"webauthn.get","challenge":"{challenge}","origin":"https://ebas.example"}"#;
+        // Android identifica la app por el hash de su certificado
+        let android = std::format!(
+            r#"{{"type":"webauthn.get","challenge":"{{challenge}}","origin":"{}","androidPackageName":"com.ebas.wallet"}}"#,
+            ANDROID_ORIGIN
+        );
+        for client_data_json in [chrome, safari, &android] {
+            assert_eq!(
+                check_client_data(&env, &contract_id, client_data_json),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_auth_long_client_data() {
+        let env = Env::default();
+        let contract_id = setup(&env);
+
+        // Más de 1024 bytes, dentro del máximo
+        let padding = "a".repeat(1500);
+        let long = std::format!(
+            r#"{{"type":"webauthn.get","challenge":"{{challenge}}","origin":"https://ebas.example","extension":"{}"}}"#,
+            padding
+        );
+        assert!(long.len() > 1024 && long.len() <= MAX_CLIENT_DATA_JSON_LEN as usize);
+        assert_eq!(check_client_data(&env, &contract_id, &long), Ok(()));
+
+        // Por encima del máximo se rechaza sin abortar
+        let padding = "a".repeat(MAX_CLIENT_DATA_JSON_LEN as usize);
+        let too_long = std::format!(
+            r#"{{"type":"webauthn.get","challenge":"{{challenge}}","origin":"https://ebas.example","extension":"{}"}}"#,
+            padding
+        );
+        assert_eq!(
+            check_client_data(&env, &contract_id, &too_long),
+            Err(Ok(Error::JsonParseError))
+        );
+    }
+}
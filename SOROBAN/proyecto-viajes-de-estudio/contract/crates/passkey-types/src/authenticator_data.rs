@@ -0,0 +1,142 @@
+// WebAuthn authenticator data parsing
+//
+// Layout (https://www.w3.org/TR/webauthn-2/#sctn-authenticator-data):
+//
+//   rpIdHash (32) | flags (1) | signCount (4, big-endian) | attestedCredentialData? | extensions?
+//
+// attestedCredentialData (registration only):
+//
+//   aaguid (16) | credentialIdLength (2, big-endian) | credentialId | credentialPublicKey (COSE_Key)
+
+use crate::cose_key;
+use soroban_sdk::{Bytes, BytesN, Env};
+
+/// User Present
+pub const FLAG_UP: u8 = 0x01;
+/// User Verified
+pub const FLAG_UV: u8 = 0x04;
+/// Attested credential data included
+pub const FLAG_AT: u8 = 0x40;
+/// Extension data included
+pub const FLAG_ED: u8 = 0x80;
+
+/// Length of the fixed part of the authenticator data
+const HEADER_LEN: u32 = 37;
+/// Length of the AAGUID and credential ID length in attested credential data
+const CREDENTIAL_HEADER_LEN: u32 = 18;
+/// Longest credential ID allowed by WebAuthn
+const MAX_CREDENTIAL_ID_LEN: u32 = 1023;
+
+/// Credential created by the authenticator during registration
+pub struct AttestedCredentialData {
+    /// Model of the authenticator (all zeros when not disclosed)
+    pub aaguid: BytesN<16>,
+    pub credential_id: Bytes,
+    /// ES256 credential public key as uncompressed SEC1, not yet checked to
+    /// be on the curve
+    pub public_key: [u8; 65],
+}
+
+/// Parsed authenticator data
+pub struct AuthenticatorData {
+    pub rp_id_hash: BytesN<32>,
+    pub flags: u8,
+    pub sign_count: u32,
+    /// Attested credential data (only with the AT flag)
+    pub attested_credential_data: Option<AttestedCredentialData>,
+    /// CBOR-encoded extensions (only with the ED flag)
+    pub extensions: Option<Bytes>,
+}
+
+impl AuthenticatorData {
+    /// The user touched the authenticator
+    pub fn user_present(&self) -> bool {
+        self.flags & FLAG_UP != 0
+    }
+
+    /// The user was verified (biometrics, PIN)
+    pub fn user_verified(&self) -> bool {
+        self.flags & FLAG_UV != 0
+    }
+}
+
+/// Parse the authenticator data, returning `None` if it is malformed
+pub fn parse(env: &Env, data: &Bytes) -> Option<AuthenticatorData> {
+    if data.len() < HEADER_LEN {
+        return None;
+    }
+
+    let mut rp_id_hash = [0u8; 32];
+    data.slice(..32).copy_into_slice(&mut rp_id_hash);
+    let flags = data.get(32)?;
+    let mut sign_count = [0u8; 4];
+    data.slice(33..HEADER_LEN).copy_into_slice(&mut sign_count);
+
+    let mut rest = data.slice(HEADER_LEN..);
+    let mut attested_credential_data = None;
+    let mut extensions = None;
+
+    if flags & FLAG_AT != 0 {
+        let (credential, len) = parse_attested_credential_data(env, &rest)?;
+        attested_credential_data = Some(credential);
+        rest = rest.slice(len..);
+    }
+    if flags & FLAG_ED != 0 {
+        if rest.is_empty() {
+            return None;
+        }
+        extensions = Some(rest);
+    } else if !rest.is_empty() {
+        // Trailing bytes that no flag accounts for
+        return None;
+    }
+
+    Some(AuthenticatorData {
+        rp_id_hash: BytesN::from_array(env, &rp_id_hash),
+        flags,
+        sign_count: u32::from_be_bytes(sign_count),
+        attested_credential_data,
+        extensions,
+    })
+}
+
+/// Parse the attested credential data at the start of `data`, returning it
+/// with the number of bytes it took
+fn parse_attested_credential_data(
+    env: &Env,
+    data: &Bytes,
+) -> Option<(AttestedCredentialData, u32)> {
+    if data.len() < CREDENTIAL_HEADER_LEN {
+        return None;
+    }
+
+    let mut aaguid = [0u8; 16];
+    data.slice(..16).copy_into_slice(&mut aaguid);
+    let credential_id_len = u32::from(data.get(16)?) << 8 | u32::from(data.get(17)?);
+    let key_start = CREDENTIAL_HEADER_LEN.checked_add(credential_id_len)?;
+    if credential_id_len == 0
+        || credential_id_len > MAX_CREDENTIAL_ID_LEN
+        || data.len() <= key_start
+    {
+        return None;
+    }
+    let credential_id = data.slice(CREDENTIAL_HEADER_LEN..key_start);
+
+    // The COSE_Key has no length prefix: decode it to find where any
+    // extensions start
+    let mut buffer = [0u8; cose_key::MAX_LEN];
+    let available = (data.len() - key_start).min(cose_key::MAX_LEN as u32);
+    let buffer = &mut buffer[..available as usize];
+    data.slice(key_start..key_start + available)
+        .copy_into_slice(buffer);
+    let (public_key, key_len) = cose_key::decode(buffer)?;
+
+    Some((
+        AttestedCredentialData {
+            aaguid: BytesN::from_array(env, &aaguid),
+            credential_id,
+            public_key,
+        },
+        key_start + key_len as u32,
+    ))
+}
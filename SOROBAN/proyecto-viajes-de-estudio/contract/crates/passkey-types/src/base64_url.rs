@@ -0,0 +1,235 @@
+// Base64 URL encoding utilities (adapted from kalepail/soroban-passkey)
+//
+// Copyright (c) 2024 Tyler van der Hoeven
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use soroban_sdk::{Bytes, Env};
+
+const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Why a base64url string could not be decoded
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DecodeError {
+    /// A byte outside the base64url alphabet
+    InvalidCharacter,
+    /// A length no encoding can produce (one character left over)
+    InvalidLength,
+    /// Padding in the middle, too much of it, or not up to a multiple of 4
+    InvalidPadding,
+    /// The last character has bits set that are not part of the data
+    NonCanonical,
+}
+
+/// Encode bytes as base64url without padding (RFC 4648 section 5)
+pub fn encode(env: &Env, data: &Bytes) -> Bytes {
+    let mut output = Bytes::new(env);
+
+    let mut acc = 0u32;
+    let mut acc_len = 0u32;
+
+    for byte in data.iter() {
+        acc = (acc << 8) | byte as u32;
+        acc_len += 8;
+
+        while acc_len >= 6 {
+            acc_len -= 6;
+            output.push_back(ALPHABET[((acc >> acc_len) & 0x3f) as usize]);
+        }
+    }
+    // The last character carries the remaining bits, padded with zeros
+    if acc_len > 0 {
+        output.push_back(ALPHABET[((acc << (6 - acc_len)) & 0x3f) as usize]);
+    }
+
+    output
+}
+
+/// Decode base64url bytes in Soroban environment
+///
+/// Padding is optional but must be complete when present. Only the canonical
+/// encoding of each value is accepted: lengths that leave a single character
+/// over and non-zero bits after the data are rejected.
+pub fn decode(env: &Env, base64_url: &Bytes) -> Result<Bytes, DecodeError> {
+    let mut output = Bytes::new(env);
+
+    // Base64url doesn't use padding, but accept it if it is well-formed
+    let mut actual_len = base64_url.len();
+    while actual_len > 0 && base64_url.get(actual_len - 1) == Some(b'=') {
+        actual_len -= 1;
+    }
+    let padding = base64_url.len() - actual_len;
+    if padding > 0 && (padding > 2 || !base64_url.len().is_multiple_of(4)) {
+        return Err(DecodeError::InvalidPadding);
+    }
+    if actual_len % 4 == 1 {
+        return Err(DecodeError::InvalidLength);
+    }
+
+    let mut acc = 0u32;
+    let mut acc_len = 0u32;
+
+    for byte in base64_url.slice(..actual_len).iter() {
+        let val = decode_char(byte)?;
+
+        acc = (acc << 6) | (val as u32);
+        acc_len += 6;
+
+        if acc_len >= 8 {
+            acc_len -= 8;
+            let byte_val = ((acc >> acc_len) & 0xFF) as u8;
+            output.push_back(byte_val);
+        }
+    }
+
+    // The 2 or 4 bits left over must be zero, or several strings would
+    // decode to the same bytes
+    if acc & ((1 << acc_len) - 1) != 0 {
+        return Err(DecodeError::NonCanonical);
+    }
+
+    Ok(output)
+}
+
+fn decode_char(byte: u8) -> Result<u8, DecodeError> {
+    match byte {
+        b'A'..=b'Z' => Ok(byte - b'A'),
+        b'a'..=b'z' => Ok(byte - b'a' + 26),
+        b'0'..=b'9' => Ok(byte - b'0' + 52),
+        b'-' => Ok(62),
+        b'_' => Ok(63),
+        b'=' => Err(DecodeError::InvalidPadding),
+        _ => Err(DecodeError::InvalidCharacter),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bytes(env: &Env, data: &str) -> Bytes {
+        Bytes::from_slice(env, data.as_bytes())
+    }
+
+    /// RFC 4648 section 10 test vectors (they contain no `+` or `/`, so they
+    /// are the same in base64url)
+    const RFC_4648_VECTORS: [(&str, &str); 7] = [
+        ("", ""),
+        ("f", "Zg"),
+        ("fo", "Zm8"),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg"),
+        ("fooba", "Zm9vYmE"),
+        ("foobar", "Zm9vYmFy"),
+    ];
+
+    #[test]
+    fn test_rfc_4648_vectors() {
+        let env = Env::default();
+        for (data, encoded) in RFC_4648_VECTORS {
+            assert_eq!(encode(&env, &bytes(&env, data)), bytes(&env, encoded));
+            assert_eq!(decode(&env, &bytes(&env, encoded)), Ok(bytes(&env, data)));
+        }
+    }
+
+    #[test]
+    fn test_rfc_4648_vectors_with_padding() {
+        let env = Env::default();
+        for (data, encoded) in [
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+        ] {
+            assert_eq!(decode(&env, &bytes(&env, encoded)), Ok(bytes(&env, data)));
+        }
+    }
+
+    #[test]
+    fn test_url_safe_alphabet() {
+        let env = Env::default();
+        // 0xfb 0xff encodes as "+/8" in standard base64
+        let data = Bytes::from_array(&env, &[0xfb, 0xff]);
+        assert_eq!(encode(&env, &data), bytes(&env, "-_8"));
+        assert_eq!(decode(&env, &bytes(&env, "-_8")), Ok(data));
+        assert_eq!(
+            decode(&env, &bytes(&env, "+/8")),
+            Err(DecodeError::InvalidCharacter)
+        );
+    }
+
+    #[test]
+    fn test_round_trip_all_lengths_and_bytes() {
+        let env = Env::default();
+        // Every length modulo 3, with bytes spread over the whole range
+        let mut data = Bytes::new(&env);
+        for i in 0..66u8 {
+            data.push_back(i.wrapping_mul(97));
+            let encoded = encode(&env, &data);
+            assert_eq!(encoded.len(), (data.len() * 4).div_ceil(3));
+            assert_eq!(decode(&env, &encoded), Ok(data.clone()));
+        }
+    }
+
+    #[test]
+    fn test_rejects_impossible_lengths() {
+        let env = Env::default();
+        for encoded in ["Z", "Zm9vY", "Zm9vYmFyZ", "Z==="] {
+            assert!(decode(&env, &bytes(&env, encoded)).is_err(), "{}", encoded);
+        }
+        assert_eq!(
+            decode(&env, &bytes(&env, "Zm9vY")),
+            Err(DecodeError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn test_rejects_non_zero_leftover_bits() {
+        let env = Env::default();
+        // "Zg" is "f"; "Zh" to "Zv" set some of the 4 unused bits
+        for encoded in ["Zh", "Zv", "Zm9", "Zm-", "Zm9vYh"] {
+            assert_eq!(
+                decode(&env, &bytes(&env, encoded)),
+                Err(DecodeError::NonCanonical),
+                "{}",
+                encoded
+            );
+        }
+    }
+
+    #[test]
+    fn test_rejects_invalid_padding() {
+        let env = Env::default();
+        for encoded in ["Zg=", "Zm8==", "Zg===", "Zm9v====", "Z=g=", "=", "Zm=8"] {
+            assert_eq!(
+                decode(&env, &bytes(&env, encoded)),
+                Err(DecodeError::InvalidPadding),
+                "{}",
+                encoded
+            );
+        }
+    }
+
+    #[test]
+    fn test_rejects_invalid_characters() {
+        let env = Env::default();
+        for encoded in ["Zm9vYm y", "Zm9v.mFy", "Zm9v\nmFy"] {
+            assert_eq!(
+                decode(&env, &bytes(&env, encoded)),
+                Err(DecodeError::InvalidCharacter),
+                "{}",
+                encoded
+            );
+        }
+    }
+}
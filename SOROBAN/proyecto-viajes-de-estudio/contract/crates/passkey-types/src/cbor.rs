@@ -0,0 +1,152 @@
+// Minimal CBOR (RFC 8949) decoder
+//
+// Only what WebAuthn structures need: definite-length integers, byte and
+// text strings, arrays, maps and simple values. Indefinite lengths, tags
+// and floats are rejected.
+
+/// Unsigned integer
+pub const MAJOR_UINT: u8 = 0;
+/// Negative integer (-1 - n)
+pub const MAJOR_NINT: u8 = 1;
+/// Byte string
+pub const MAJOR_BYTES: u8 = 2;
+/// Text string
+pub const MAJOR_TEXT: u8 = 3;
+/// Array
+pub const MAJOR_ARRAY: u8 = 4;
+/// Map
+pub const MAJOR_MAP: u8 = 5;
+/// Simple values (false, true, null)
+pub const MAJOR_SIMPLE: u8 = 7;
+
+/// How deep `skip` follows nested arrays and maps
+const MAX_DEPTH: u8 = 8;
+
+/// Reads CBOR items one after the other from a byte slice
+pub struct Decoder<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Decoder { data, position: 0 }
+    }
+
+    /// Number of bytes read so far
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Whether every byte has been read
+    pub fn is_empty(&self) -> bool {
+        self.position == self.data.len()
+    }
+
+    /// Read an integer (major types 0 and 1)
+    pub fn int(&mut self) -> Option<i64> {
+        match self.header()? {
+            (MAJOR_UINT, value) => i64::try_from(value).ok(),
+            (MAJOR_NINT, value) => i64::try_from(value).ok().map(|value| -1 - value),
+            _ => None,
+        }
+    }
+
+    /// Read a byte string
+    pub fn bytes(&mut self) -> Option<&'a [u8]> {
+        match self.header()? {
+            (MAJOR_BYTES, len) => self.take(len),
+            _ => None,
+        }
+    }
+
+    /// Read a UTF-8 text string
+    pub fn text(&mut self) -> Option<&'a str> {
+        match self.header()? {
+            (MAJOR_TEXT, len) => core::str::from_utf8(self.take(len)?).ok(),
+            _ => None,
+        }
+    }
+
+    /// Read the header of a map, returning its number of entries
+    pub fn map(&mut self) -> Option<u64> {
+        match self.header()? {
+            (MAJOR_MAP, len) => Some(len),
+            _ => None,
+        }
+    }
+
+    /// Skip the next item, including everything nested in it
+    pub fn skip(&mut self) -> Option<()> {
+        self.skip_nested(0)
+    }
+
+    /// Read the next item without decoding it, returning its encoded bytes
+    pub fn raw(&mut self) -> Option<&'a [u8]> {
+        let start = self.position;
+        self.skip()?;
+        Some(&self.data[start..self.position])
+    }
+
+    fn skip_nested(&mut self, depth: u8) -> Option<()> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        match self.header()? {
+            (MAJOR_UINT | MAJOR_NINT | MAJOR_SIMPLE, _) => {}
+            (MAJOR_BYTES | MAJOR_TEXT, len) => {
+                self.take(len)?;
+            }
+            (MAJOR_ARRAY, len) => {
+                for _ in 0..len {
+                    self.skip_nested(depth + 1)?;
+                }
+            }
+            (MAJOR_MAP, len) => {
+                for _ in 0..len.checked_mul(2)? {
+                    self.skip_nested(depth + 1)?;
+                }
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+
+    /// Read an item header: major type and argument (value or length)
+    fn header(&mut self) -> Option<(u8, u64)> {
+        let initial = *self.data.get(self.position)?;
+        self.position += 1;
+
+        let major = initial >> 5;
+        let argument = match initial & 0x1f {
+            info @ 0..=23 => info as u64,
+            24 => self.be_uint(1)?,
+            25 => self.be_uint(2)?,
+            26 => self.be_uint(4)?,
+            27 => self.be_uint(8)?,
+            // Reserved values and indefinite lengths
+            _ => return None,
+        };
+        // Tags and floats are not used by WebAuthn
+        if major == 6 || (major == MAJOR_SIMPLE && argument > 23) {
+            return None;
+        }
+
+        Some((major, argument))
+    }
+
+    fn be_uint(&mut self, len: u64) -> Option<u64> {
+        Some(
+            self.take(len)?
+                .iter()
+                .fold(0u64, |value, byte| (value << 8) | *byte as u64),
+        )
+    }
+
+    fn take(&mut self, len: u64) -> Option<&'a [u8]> {
+        let end = self.position.checked_add(usize::try_from(len).ok()?)?;
+        let slice = self.data.get(self.position..end)?;
+        self.position = end;
+        Some(slice)
+    }
+}
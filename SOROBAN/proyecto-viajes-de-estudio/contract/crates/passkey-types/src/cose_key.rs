@@ -0,0 +1,69 @@
+// COSE_Key (RFC 9053) decoding for WebAuthn credential public keys
+//
+// An ES256 passkey key is a CBOR map with integer labels:
+//
+//   1 (kty): 2 (EC2) | 3 (alg): -7 (ES256) | -1 (crv): 1 (P-256) | -2 (x): bstr | -3 (y): bstr
+
+use crate::cbor::Decoder;
+
+/// Largest COSE_Key accepted, leaving room for optional parameters
+pub const MAX_LEN: usize = 256;
+
+const LABEL_KTY: i64 = 1;
+const LABEL_ALG: i64 = 3;
+const LABEL_CRV: i64 = -1;
+const LABEL_X: i64 = -2;
+const LABEL_Y: i64 = -3;
+
+const KTY_EC2: i64 = 2;
+const ALG_ES256: i64 = -7;
+const CRV_P256: i64 = 1;
+
+/// Decode an ES256 COSE_Key at the start of `data`
+///
+/// Returns the key as uncompressed SEC1 (0x04 || X || Y) and the number of
+/// bytes the key took, or `None` if it is malformed or not an EC2/ES256/P-256
+/// key. The point itself is not checked to be on the curve.
+pub fn decode(data: &[u8]) -> Option<([u8; 65], usize)> {
+    let mut decoder = Decoder::new(data);
+
+    let mut kty = None;
+    let mut alg = None;
+    let mut crv = None;
+    let mut x = None;
+    let mut y = None;
+
+    for _ in 0..decoder.map()? {
+        let label = decoder.int()?;
+        let seen = match label {
+            LABEL_KTY => kty.replace(decoder.int()?).is_some(),
+            LABEL_ALG => alg.replace(decoder.int()?).is_some(),
+            LABEL_CRV => crv.replace(decoder.int()?).is_some(),
+            LABEL_X => x.replace(decoder.bytes()?).is_some(),
+            LABEL_Y => y.replace(decoder.bytes()?).is_some(),
+            // Other parameters (key ID, key ops, ...) are ignored
+            _ => {
+                decoder.skip()?;
+                false
+            }
+        };
+        // Duplicate labels make the key ambiguous
+        if seen {
+            return None;
+        }
+    }
+
+    if kty? != KTY_EC2 || alg? != ALG_ES256 || crv? != CRV_P256 {
+        return None;
+    }
+    let (x, y) = (x?, y?);
+    if x.len() != 32 || y.len() != 32 {
+        return None;
+    }
+
+    let mut sec1 = [0u8; 65];
+    sec1[0] = 0x04;
+    sec1[1..33].copy_from_slice(x);
+    sec1[33..].copy_from_slice(y);
+    Some((sec1, decoder.position()))
+}
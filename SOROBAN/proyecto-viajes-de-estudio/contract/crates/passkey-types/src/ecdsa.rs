@@ -0,0 +1,128 @@
+// secp256r1 ECDSA signature encoding
+//
+// WebAuthn authenticators return DER-encoded signatures:
+//
+//   SEQUENCE { r INTEGER, s INTEGER }  ->  30 len 02 len r 02 len s
+//
+// while the host expects the fixed-size r || s form with s in low form.
+
+use soroban_sdk::Bytes;
+
+/// Order of the secp256r1 base point (big-endian)
+pub const ORDER: [u8; 32] = [
+    0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xbc, 0xe6, 0xfa, 0xad, 0xa7, 0x17, 0x9e, 0x84, 0xf3, 0xb9, 0xca, 0xc2, 0xfc, 0x63, 0x25, 0x51,
+];
+
+/// Half of the secp256r1 order, the largest `s` accepted by the host (big-endian)
+pub const HALF_ORDER: [u8; 32] = [
+    0x7f, 0xff, 0xff, 0xff, 0x80, 0x00, 0x00, 0x00, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xde, 0x73, 0x7d, 0x56, 0xd3, 0x8b, 0xcf, 0x42, 0x79, 0xdc, 0xe5, 0x61, 0x7e, 0x31, 0x92, 0xa8,
+];
+
+/// Longest DER signature: two 33-byte integers (with a sign byte) and headers
+pub const MAX_DER_LEN: u32 = 72;
+
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_INTEGER: u8 = 0x02;
+
+/// Decode a signature given either in DER or as raw r || s (64 bytes) into
+/// the r || s form the host verifies, with a high `s` replaced by its low
+/// form. Returns `None` if it is malformed or r or s are out of range.
+pub fn parse(signature: &Bytes) -> Option<[u8; 64]> {
+    if signature.len() > MAX_DER_LEN {
+        return None;
+    }
+    let mut buffer = [0u8; MAX_DER_LEN as usize];
+    let buffer = &mut buffer[..signature.len() as usize];
+    signature.copy_into_slice(buffer);
+
+    // A raw r || s practically never parses as strict DER, so DER is tried first
+    let mut signature = match from_der(buffer) {
+        Some(signature) => signature,
+        None => buffer.try_into().ok()?,
+    };
+    if !is_valid(&signature) {
+        return None;
+    }
+    to_low_s(&mut signature);
+    Some(signature)
+}
+
+/// Whether r and s are non-zero scalars below the curve order
+pub fn is_valid(signature: &[u8; 64]) -> bool {
+    let (r, s) = signature.split_at(32);
+    !is_zero(r) && r < &ORDER[..] && !is_zero(s) && s < &ORDER[..]
+}
+
+/// Check whether a big-endian scalar is zero
+pub fn is_zero(scalar: &[u8]) -> bool {
+    scalar.iter().all(|b| *b == 0)
+}
+
+/// Replace a high `s` by `n - s`, which is an equally valid signature
+/// that the host accepts. `s` must already be below the curve order.
+pub fn to_low_s(signature: &mut [u8; 64]) {
+    if signature[32..] <= HALF_ORDER[..] {
+        return;
+    }
+
+    let mut borrow = 0i16;
+    for i in (0..32).rev() {
+        let difference = ORDER[i] as i16 - signature[32 + i] as i16 - borrow;
+        borrow = (difference < 0) as i16;
+        signature[32 + i] = difference.rem_euclid(256) as u8;
+    }
+}
+
+/// Decode a strict DER signature into r || s, returning `None` if it is
+/// malformed (wrong tags or lengths, non-minimal or negative integers,
+/// integers longer than 32 bytes or trailing data)
+pub fn from_der(der: &[u8]) -> Option<[u8; 64]> {
+    let (&tag, rest) = der.split_first()?;
+    let (&len, body) = rest.split_first()?;
+    // The longest signature (72 bytes) never needs a long-form length
+    if tag != TAG_SEQUENCE || len >= 0x80 || body.len() != len as usize {
+        return None;
+    }
+
+    let mut signature = [0u8; 64];
+    let body = read_integer(body, &mut signature[..32])?;
+    let body = read_integer(body, &mut signature[32..])?;
+    if !body.is_empty() {
+        return None;
+    }
+
+    Some(signature)
+}
+
+/// Read a positive INTEGER into a 32-byte big-endian scalar, returning the
+/// remaining input
+fn read_integer<'a>(der: &'a [u8], scalar: &mut [u8]) -> Option<&'a [u8]> {
+    let (&tag, rest) = der.split_first()?;
+    let (&len, rest) = rest.split_first()?;
+    let len = len as usize;
+    if tag != TAG_INTEGER || len == 0 || rest.len() < len {
+        return None;
+    }
+    let (mut value, rest) = rest.split_at(len);
+
+    // Negative numbers are invalid for r and s
+    if value[0] & 0x80 != 0 {
+        return None;
+    }
+    // A leading zero is only allowed to keep the next byte positive
+    if value[0] == 0 && len > 1 {
+        if value[1] & 0x80 == 0 {
+            return None;
+        }
+        value = &value[1..];
+    }
+    if value.len() > scalar.len() {
+        return None;
+    }
+
+    let offset = scalar.len() - value.len();
+    scalar[offset..].copy_from_slice(value);
+    Some(rest)
+}
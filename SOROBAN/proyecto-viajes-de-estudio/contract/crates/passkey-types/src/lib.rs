@@ -0,0 +1,16 @@
+//! Encodings shared by the passkey account contracts
+//!
+//! - `public_key`: secp256r1 public keys (SEC1 and COSE_Key)
+//! - `ecdsa`: secp256r1 signatures (DER and r || s, low-S normalization)
+//! - `authenticator_data`: WebAuthn authenticator data
+//! - `base64_url`: base64url challenges
+//! - `cbor` / `cose_key`: the CBOR subset WebAuthn uses
+
+#![no_std]
+
+pub mod authenticator_data;
+pub mod base64_url;
+pub mod cbor;
+pub mod cose_key;
+pub mod ecdsa;
+pub mod public_key;
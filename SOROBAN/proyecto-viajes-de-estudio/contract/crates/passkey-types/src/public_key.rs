@@ -0,0 +1,51 @@
+// secp256r1 (P-256) public key validation
+//
+// Accepted encodings:
+//
+//   compressed SEC1:   0x02/0x03 || X (33 bytes)
+//   uncompressed SEC1: 0x04 || X || Y (65 bytes)
+//   raw coordinates:   X || Y (64 bytes)
+//   COSE_Key:          EC2/ES256/P-256 map from attestedCredentialData (77+ bytes)
+
+use crate::cose_key;
+use p256::{elliptic_curve::sec1::ToEncodedPoint, PublicKey};
+use soroban_sdk::Bytes;
+
+/// Validate that `key` encodes a point on the P-256 curve and return it in
+/// uncompressed SEC1 form, or `None` if it is malformed or not on the curve
+pub fn to_uncompressed(key: &Bytes) -> Option<[u8; 65]> {
+    let mut sec1 = [0u8; 65];
+    let len = match key.len() {
+        33 | 65 => {
+            key.copy_into_slice(&mut sec1[..key.len() as usize]);
+            key.len() as usize
+        }
+        64 => {
+            sec1[0] = 0x04;
+            key.copy_into_slice(&mut sec1[1..]);
+            65
+        }
+        // No SEC1 form has any other length, so it can only be a COSE_Key
+        len if len as usize <= cose_key::MAX_LEN => {
+            let mut buffer = [0u8; cose_key::MAX_LEN];
+            let buffer = &mut buffer[..len as usize];
+            key.copy_into_slice(buffer);
+
+            let (point, consumed) = cose_key::decode(buffer)?;
+            if consumed != buffer.len() {
+                return None;
+            }
+            sec1 = point;
+            65
+        }
+        _ => return None,
+    };
+
+    // Rejects the identity, unknown tags and points off the curve, and
+    // decompresses compressed keys
+    let point = PublicKey::from_sec1_bytes(&sec1[..len]).ok()?;
+
+    let mut uncompressed = [0u8; 65];
+    uncompressed.copy_from_slice(point.to_encoded_point(false).as_bytes());
+    Some(uncompressed)
+}
@@ -1,64 +0,0 @@
-#![no_std]
-use soroban_sdk::{
-    contract, contracterror, contractimpl, symbol_short, vec, Address, BytesN, Env, Symbol,
-};
-
-#[contract]
-pub struct Factory;
-
-#[contracterror]
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
-pub enum Error {
-    NotInited = 1,
-    AlreadyInited = 2,
-}
-
-const STORAGE_KEY_WASM_HASH: Symbol = symbol_short!("hash");
-
-#[contractimpl]
-impl Factory {
-    /// Extiende el TTL del contrato
-    pub fn extend_ttl(env: Env) {
-        let max_ttl = env.storage().max_ttl();
-        let contract_address = env.current_contract_address();
-
-        env.storage().instance().extend_ttl(max_ttl, max_ttl);
-        env.deployer()
-            .extend_ttl(contract_address.clone(), max_ttl, max_ttl);
-        env.deployer()
-            .extend_ttl_for_code(contract_address.clone(), max_ttl, max_ttl);
-        env.deployer()
-            .extend_ttl_for_contract_instance(contract_address.clone(), max_ttl, max_ttl);
-    }
-
-    /// Inicializa el factory con el hash del contrato WebAuthn
-    pub fn init(env: Env, wasm_hash: BytesN<32>) -> Result<(), Error> {
-        if env.storage().instance().has(&STORAGE_KEY_WASM_HASH) {
-            return Err(Error::AlreadyInited);
-        }
-
-        env.storage()
-            .instance()
-            .set(&STORAGE_KEY_WASM_HASH, &wasm_hash);
-
-        Self::extend_ttl(env);
-
-        Ok(())
-    }
-
-    /// Despliega una nueva cuenta WebAuthn
-    pub fn deploy(env: Env, salt: BytesN<32>, pk: BytesN<65>) -> Result<Address, Error> {
-        let wasm_hash = env
-            .storage()
-            .instance()
-            .get::<Symbol, BytesN<32>>(&STORAGE_KEY_WASM_HASH)
-            .ok_or(Error::NotInited)?;
-
-        let address = env.deployer().with_current_contract(salt).deploy(wasm_hash);
-        let () = env.invoke_contract(&address, &symbol_short!("init"), vec![&env, pk.to_val()]);
-
-        Self::extend_ttl(env);
-
-        Ok(address)
-    }
-}
\ No newline at end of file
@@ -1,43 +0,0 @@
-#![cfg(test)]
-extern crate std;
-
-use super::*;
-
-// Placeholder tests for the PasskeyAccount contract
-// These tests verify the basic structure of the contract
-
-#[test]
-fn test_passkey_account_contract_exists() {
-    // Test that the contract can be defined
-    let _contract = PasskeyAccount;
-    // If this compiles without errors, the contract structure is valid
-}
-
-#[test]
-fn test_data_key_enum_exists() {
-    // Verify that DataKey enum can be instantiated
-    let _owner_key = DataKey::Owner;
-    let _cred_id_key = DataKey::CredentialId;
-}
-
-#[test]
-fn test_error_codes_valid() {
-    // Verify that error codes are defined correctly
-    assert_eq!(Error::AlreadyInitialized as u32, 1);
-    assert_eq!(Error::NotInitialized as u32, 2);
-    assert_eq!(Error::InvalidPublicKey as u32, 3);
-    assert_eq!(Error::InvalidSignature as u32, 4);
-}
-
-#[test]
-fn test_error_enum_values() {
-    // Test error enum discriminants
-    let err1 = Error::AlreadyInitialized;
-    let err2 = Error::NotInitialized;
-    let err3 = Error::InvalidPublicKey;
-    let err4 = Error::InvalidSignature;
-    
-    assert_ne!(err1, err2);
-    assert_ne!(err2, err3);
-    assert_ne!(err3, err4);
-}
@@ -42,7 +42,7 @@ curl -s "https://friendbot.stellar.org?addr=$ALICE_ADDRESS" > /dev/null || echo
 echo ""
 echo "🚀 Deploying contract..."
 CONTRACT_ID=$(soroban contract deploy \
-    --wasm target/wasm32-unknown-unknown/release/passkey_account.wasm \
+    --wasm target/wasm32v1-none/release/passkey_account.wasm \
     --source alice \
     --network testnet)
 
@@ -34,16 +34,16 @@ echo -e "${GREEN}✅ Rust instalado: $(rustc --version)${NC}"
 echo ""
 
 # Step 2: Add wasm32 target
-echo -e "${YELLOW}[2/5]${NC} Agregando target wasm32-unknown-unknown..."
-rustup target add wasm32-unknown-unknown 2>/dev/null || echo "Target ya instalado"
-echo -e "${GREEN}✅ Target wasm32 listo${NC}"
+echo -e "${YELLOW}[2/5]${NC} Agregando target wasm32v1-none..."
+rustup target add wasm32v1-none 2>/dev/null || echo "Target ya instalado"
+echo -e "${GREEN}✅ Target wasm32v1-none listo${NC}"
 echo ""
 
 # Step 3: Build contract
 echo -e "${YELLOW}[3/5]${NC} Compilando contrato Soroban..."
 cd "$PROJECT_ROOT/contract"
 echo "   📦 Descargando dependencias..."
-cargo build --target wasm32-unknown-unknown --release --quiet
+cargo build --target wasm32v1-none --release --quiet
 echo -e "${GREEN}✅ Contrato compilado exitosamente${NC}"
 echo ""
 